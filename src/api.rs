@@ -0,0 +1,616 @@
+use crate::models::{
+    billing::Invoice,
+    calendar_state::CalendarDate,
+    day_data::DayData,
+    flexitime::{BalanceAdjustment, FlexitimeBalance},
+    ics::{BusyTime, DaysOffImport},
+    import::{ConflictMode, ImportOptions, ImportPreview, ImportSummary},
+    project::Project,
+    timer::TimerStatus,
+    user_settings::UserSettings,
+    vacation::VacationLedger,
+};
+use leptos::prelude::*;
+use leptos::server_fn::codec::Json;
+
+#[cfg(feature = "ssr")]
+fn store() -> std::sync::Arc<dyn crate::store::TimeStore> {
+    expect_context::<crate::app_state::AppState>().store
+}
+
+/// User making the current request
+#[cfg(feature = "ssr")]
+fn current_user() -> String {
+    let headers = use_context::<axum::http::request::Parts>()
+        .map(|parts| parts.headers)
+        .unwrap_or_default();
+    expect_context::<crate::app_state::AppState>().user(&headers)
+}
+
+/// Saved settings of the current user, or the server defaults if they saved none yet
+#[cfg(feature = "ssr")]
+fn user_settings() -> Result<UserSettings, ServerFnError> {
+    expect_context::<crate::app_state::AppState>()
+        .user_settings(&current_user())
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Read a CSV file and look up the days already logged on its dates
+#[cfg(feature = "ssr")]
+fn read_import(content: &str, options: &ImportOptions) -> Result<ImportPreview, ServerFnError> {
+    use crate::models::import::{parse_row, ColumnMapping, ImportRow};
+    use std::collections::HashMap;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter.byte())
+        .flexible(true)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .iter()
+        .map(|header| header.trim().to_string())
+        .collect::<Vec<_>>();
+    let mapping = options
+        .mapping
+        .clone()
+        .unwrap_or_else(|| ColumnMapping::guess(&headers));
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let (line, entry) = match record {
+            // Rows left blank between the data, as spreadsheets often export them
+            Ok(record) if record.iter().all(|field| field.trim().is_empty()) => continue,
+            Ok(record) => {
+                let fields = record.iter().map(str::to_string).collect::<Vec<_>>();
+                (
+                    record.position().map_or(0, |position| position.line()),
+                    parse_row(&fields, &mapping, options.decimal),
+                )
+            }
+            Err(e) => (
+                e.position().map_or(0, |position| position.line()),
+                Err(e.to_string()),
+            ),
+        };
+        rows.push(ImportRow {
+            line,
+            entry,
+            existing: None,
+        });
+    }
+
+    let dates = rows
+        .iter()
+        .filter_map(|row| row.entry.as_ref().ok().map(|(date, _)| *date));
+    if let (Some(from), Some(to)) = (dates.clone().min(), dates.max()) {
+        let logged = store()
+            .get_range(from, to)
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        for row in &mut rows {
+            if let Ok((date, _)) = &row.entry {
+                row.existing = logged.get(date).cloned();
+            }
+        }
+    }
+
+    Ok(ImportPreview {
+        headers,
+        mapping,
+        rows,
+    })
+}
+
+/// Load all logged days of a month
+#[server]
+pub async fn load_month(
+    year: i32,
+    month: u32,
+) -> Result<Vec<(CalendarDate, DayData)>, ServerFnError> {
+    store()
+        .get_range((year, month, 1), (year, month, 31))
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Save the time logged on a day, or the absence recorded for it
+#[server(input = Json)]
+pub async fn save_day(year: i32, month: u32, day: u32, data: DayData) -> Result<(), ServerFnError> {
+    use crate::models::day_data::Session;
+
+    let projects = store()
+        .list_projects()
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    // Sessions already on an inactive project keep it, new ones cannot pick it
+    let logged_projects = store()
+        .get_range((year, month, day), (year, month, day))
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .iter()
+        .flat_map(|(_, logged)| logged.sessions())
+        .filter_map(|session| session.project)
+        .collect::<Vec<_>>();
+    // The duration of a session with a recorded span is derived again rather than trusted
+    let sessions = data
+        .sessions()
+        .iter()
+        .map(|session| {
+            let checked = match &session.span {
+                Some(span) => {
+                    span.validate().map_err(ServerFnError::new)?;
+                    Session::from_span(span.clone())
+                }
+                None if session.minutes >= 60 => {
+                    return Err(ServerFnError::new("minutes must be below 60"));
+                }
+                None => Session::new(session.hours, session.minutes),
+            };
+            if let Some(id) = session.project {
+                match projects.iter().find(|project| project.id == id) {
+                    None => return Err(ServerFnError::new("unknown project")),
+                    Some(project) if !project.active && !logged_projects.contains(&id) => {
+                        return Err(ServerFnError::new(format!(
+                            "project {} is inactive",
+                            project.name
+                        )));
+                    }
+                    Some(_) => {}
+                }
+            }
+            Ok(checked
+                .with_note(session.note.trim())
+                .with_project(session.project)
+                .with_tags(&session.tags))
+        })
+        .collect::<Result<Vec<_>, ServerFnError>>()?;
+    let data = DayData::from_sessions(sessions).with_kind(data.kind());
+    if data.to_hours() > 24.0 {
+        return Err(ServerFnError::new("a day cannot log more than 24 hours"));
+    }
+    store()
+        .upsert_day((year, month, day), data)
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Read a CSV file without storing anything, to show what importing it would do
+#[server(input = Json)]
+pub async fn preview_import(
+    content: String,
+    options: ImportOptions,
+) -> Result<ImportPreview, ServerFnError> {
+    read_import(&content, &options)
+}
+
+/// Store the days of a CSV file, resolving dates already logged as `conflict` says
+#[server(input = Json)]
+pub async fn import_csv(
+    content: String,
+    options: ImportOptions,
+    conflict: ConflictMode,
+) -> Result<ImportSummary, ServerFnError> {
+    use crate::models::import::merge_days;
+    use std::collections::{btree_map::Entry, BTreeMap, HashMap};
+
+    let preview = read_import(&content, &options)?;
+    let mut summary = ImportSummary::default();
+    // Rows of the same date are combined into one day before it is stored
+    let mut days = BTreeMap::<CalendarDate, DayData>::new();
+    let mut logged = HashMap::new();
+    for row in preview.rows {
+        let Ok((date, data)) = row.entry else {
+            summary.failed += 1;
+            continue;
+        };
+        if let Some(existing) = row.existing {
+            logged.insert(date, existing);
+        }
+        match days.entry(date) {
+            Entry::Vacant(entry) => {
+                entry.insert(data);
+            }
+            Entry::Occupied(mut entry) => match merge_days(entry.get(), &data) {
+                Ok(merged) => *entry.get_mut() = merged,
+                Err(_) => summary.failed += 1,
+            },
+        }
+    }
+
+    let store = store();
+    for (date, data) in days {
+        let data = match (logged.get(&date), conflict) {
+            (None, _) => {
+                summary.added += 1;
+                data
+            }
+            (Some(_), ConflictMode::Skip) => {
+                summary.skipped += 1;
+                continue;
+            }
+            (Some(_), ConflictMode::Overwrite) => {
+                summary.overwritten += 1;
+                data
+            }
+            (Some(existing), ConflictMode::Merge) => match merge_days(existing, &data) {
+                Ok(merged) => {
+                    summary.merged += 1;
+                    merged
+                }
+                Err(_) => {
+                    summary.failed += 1;
+                    continue;
+                }
+            },
+        };
+        store
+            .upsert_day(date, data)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+    }
+    Ok(summary)
+}
+
+/// Remove the time logged on a day
+#[server]
+pub async fn delete_day(year: i32, month: u32, day: u32) -> Result<(), ServerFnError> {
+    store()
+        .delete_day((year, month, day))
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Get the settings of the current user
+#[server]
+pub async fn get_settings() -> Result<UserSettings, ServerFnError> {
+    user_settings()
+}
+
+/// Read the days off of an iCalendar file, to be saved with the settings
+#[server(input = Json)]
+pub async fn read_days_off(content: String) -> Result<DaysOffImport, ServerFnError> {
+    use crate::models::ics::{days_off, parse_events};
+
+    let events = parse_events(&content).map_err(ServerFnError::new)?;
+    Ok(days_off(events))
+}
+
+/// Busy time of each day of a date range, read from the meetings of an iCalendar file
+#[server(input = Json)]
+pub async fn suggest_hours(
+    content: String,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+    email: String,
+) -> Result<BusyTime, ServerFnError> {
+    use crate::models::ics::{busy_time, parse_events};
+
+    if from > to {
+        return Err(ServerFnError::new("the range ends before it starts"));
+    }
+    let events = parse_events(&content).map_err(ServerFnError::new)?;
+    Ok(busy_time(events, from, to, &email))
+}
+
+/// Replace the settings of the current user
+#[server(input = Json)]
+pub async fn save_settings(settings: UserSettings) -> Result<(), ServerFnError> {
+    use crate::models::friday_cycle::FridayCycle;
+
+    let schedule = &settings.work_schedule;
+    if schedule.daily_minutes.iter().any(|minutes| *minutes > 24 * 60) {
+        return Err(ServerFnError::new("a day cannot expect more than 24 hours"));
+    }
+    let friday_cycle = schedule.friday_cycle;
+    if FridayCycle::new(friday_cycle.anchor(), friday_cycle.interval_weeks()).is_none() {
+        return Err(ServerFnError::new("the Friday cycle must start on a Friday"));
+    }
+
+    store()
+        .save_settings(&current_user(), &settings)
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Load the Fridays of a month whose working status overrides the Friday cycle
+#[server]
+pub async fn load_friday_overrides(
+    year: i32,
+    month: u32,
+) -> Result<Vec<(CalendarDate, bool)>, ServerFnError> {
+    store()
+        .list_friday_overrides((year, month, 1), (year, month, 31))
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Mark a Friday as worked or free, dropping the override when it matches the Friday cycle
+#[server]
+pub async fn set_working_friday(
+    year: i32,
+    month: u32,
+    day: u32,
+    working: bool,
+) -> Result<(), ServerFnError> {
+    let date = chrono::NaiveDate::from_ymd_opt(year, month, day)
+        .ok_or_else(|| ServerFnError::new("invalid date"))?;
+    let friday_cycle = user_settings()?.work_schedule.friday_cycle;
+    let working = (working != friday_cycle.is_working_friday(date)).then_some(working);
+    store()
+        .set_friday_override((year, month, day), working)
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Load all flexitime balance adjustments
+#[server]
+pub async fn load_adjustments() -> Result<Vec<BalanceAdjustment>, ServerFnError> {
+    store()
+        .list_adjustments((0, 1, 1), (9999, 12, 31))
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Change the flexitime balance by hand on a date
+#[server]
+pub async fn add_adjustment(
+    year: i32,
+    month: u32,
+    day: u32,
+    minutes: i32,
+    note: String,
+) -> Result<(), ServerFnError> {
+    if chrono::NaiveDate::from_ymd_opt(year, month, day).is_none() {
+        return Err(ServerFnError::new("invalid date"));
+    }
+    store()
+        .add_adjustment((year, month, day), minutes, note.trim())
+        .map(|_| ())
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Remove a flexitime balance adjustment
+#[server]
+pub async fn delete_adjustment(id: u64) -> Result<(), ServerFnError> {
+    store()
+        .delete_adjustment(id)
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Flexitime balance carried into a month and left at its end
+#[server]
+pub async fn load_flexitime_balance(
+    year: i32,
+    month: u32,
+) -> Result<FlexitimeBalance, ServerFnError> {
+    use crate::models::{flexitime::flexitime_balance, work_calendar::WorkCalendar};
+    use chrono::{Datelike, Days, Local, Months, NaiveDate};
+
+    let month_start = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| ServerFnError::new("invalid month"))?;
+    let month_end = month_start
+        .checked_add_months(Months::new(1))
+        .and_then(|next_month| next_month.pred_opt())
+        .ok_or_else(|| ServerFnError::new("invalid month"))?;
+    let end = (month_end.year(), month_end.month(), month_end.day());
+
+    let store = store();
+    let settings = user_settings()?;
+    let account = &settings.flexitime;
+    let days = store
+        .get_range((0, 1, 1), end)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let adjustments = store
+        .list_adjustments((0, 1, 1), end)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    // Without a configured start the account opens on the first logged day or adjustment, so
+    // nothing is counted before them
+    let start = account.start.unwrap_or_else(|| {
+        days.first()
+            .map(|(date, _)| *date)
+            .into_iter()
+            .chain(adjustments.first().map(|adjustment| adjustment.date))
+            .min()
+            .and_then(|(year, month, day)| NaiveDate::from_ymd_opt(year, month, day))
+            .unwrap_or(month_end + Days::new(1))
+    });
+    let friday_overrides = store
+        .list_friday_overrides((start.year(), start.month(), start.day()), end)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let work_calendar = WorkCalendar::from_settings(&settings, friday_overrides);
+    let hours_data = days.into_iter().collect();
+    let today = Local::now().date_naive();
+    let balance_until = |until| {
+        flexitime_balance(
+            start,
+            account.opening_minutes,
+            until,
+            &hours_data,
+            &work_calendar,
+            &adjustments,
+            today,
+        )
+    };
+
+    Ok(FlexitimeBalance {
+        carried_in: balance_until(month_start.pred_opt().unwrap_or(month_start)),
+        closing: balance_until(month_end),
+    })
+}
+
+/// Vacation days granted, carried over, taken and left in a year
+#[server]
+pub async fn load_vacation_ledger(year: i32) -> Result<VacationLedger, ServerFnError> {
+    use crate::models::{vacation::vacation_ledger, work_calendar::WorkCalendar};
+
+    let store = store();
+    let settings = user_settings()?;
+    let from = (settings.vacation.first_year.unwrap_or(year).min(year), 1, 1);
+    let to = (year, 12, 31);
+    let entries = store
+        .get_range(from, to)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let friday_overrides = store
+        .list_friday_overrides(from, to)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let work_calendar = WorkCalendar::from_settings(&settings, friday_overrides);
+
+    Ok(vacation_ledger(
+        &settings.vacation,
+        year,
+        &entries,
+        &work_calendar,
+    ))
+}
+
+/// Load all projects, including inactive ones
+#[server]
+pub async fn load_projects() -> Result<Vec<Project>, ServerFnError> {
+    store()
+        .list_projects()
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Add a new active project
+#[server]
+pub async fn add_project(name: String, colour: String) -> Result<(), ServerFnError> {
+    use crate::models::project::is_hex_colour;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServerFnError::new("a project needs a name"));
+    }
+    if !is_hex_colour(colour.trim()) {
+        return Err(ServerFnError::new("a project colour must look like #3a7bd5"));
+    }
+    store()
+        .add_project(name, colour.trim())
+        .map(|_| ())
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Rename, recolour, archive or reactivate a project
+#[server(input = Json)]
+pub async fn update_project(project: Project) -> Result<(), ServerFnError> {
+    use crate::models::project::is_hex_colour;
+
+    if project.name.trim().is_empty() {
+        return Err(ServerFnError::new("a project needs a name"));
+    }
+    if !is_hex_colour(project.colour.trim()) {
+        return Err(ServerFnError::new("a project colour must look like #3a7bd5"));
+    }
+    let mut rates = project.rates;
+    rates.sort_by_key(|rate| rate.from);
+    rates.dedup_by_key(|rate| rate.from);
+    store()
+        .update_project(&Project {
+            name: project.name.trim().to_string(),
+            colour: project.colour.trim().to_string(),
+            rates,
+            ..project
+        })
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Load all issued invoices
+#[server]
+pub async fn load_invoices() -> Result<Vec<Invoice>, ServerFnError> {
+    store()
+        .list_invoices()
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Invoice the time logged to a project in a month under the next invoice number
+#[server]
+pub async fn issue_invoice(project: u64, year: i32, month: u32) -> Result<Invoice, ServerFnError> {
+    use crate::models::billing::billable_lines;
+    use chrono::{Days, Local};
+
+    let store = store();
+    let Some(project) = store
+        .list_projects()
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .into_iter()
+        .find(|other| other.id == project)
+    else {
+        return Err(ServerFnError::new("unknown project"));
+    };
+    let entries = store
+        .get_range((year, month, 1), (year, month, 31))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let lines = billable_lines(&project, &entries);
+    if lines.is_empty() {
+        return Err(ServerFnError::new(
+            "no time with an hourly rate was logged to the project that month",
+        ));
+    }
+
+    let billing = user_settings()?.billing;
+    let issued = Local::now().date_naive();
+    store
+        .issue_invoice(Invoice {
+            sequence: 0,
+            issued,
+            due: issued + Days::new(billing.payment_days.into()),
+            period: (year, month),
+            project: project.id,
+            client: project.client,
+            issuer: billing.issuer,
+            currency: billing.currency,
+            lines,
+        })
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or_else(|| {
+            ServerFnError::new(format!(
+                "the project was already invoiced for {year}-{month:02}"
+            ))
+        })
+}
+
+/// Get the stopwatch status
+#[server]
+pub async fn get_timer_status() -> Result<TimerStatus, ServerFnError> {
+    let timer = store()
+        .load_timer()
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let now = chrono::Local::now().naive_local();
+    Ok(timer.unwrap_or_default().status(now))
+}
+
+/// Start or resume the stopwatch
+#[server]
+pub async fn start_timer() -> Result<TimerStatus, ServerFnError> {
+    let store = store();
+    let mut timer = store
+        .load_timer()
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .unwrap_or_default();
+    let now = chrono::Local::now().naive_local();
+    timer.start(now);
+    store
+        .save_timer(Some(&timer))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    Ok(timer.status(now))
+}
+
+/// Pause the stopwatch, keeping the time run so far
+#[server]
+pub async fn pause_timer() -> Result<TimerStatus, ServerFnError> {
+    let store = store();
+    let Some(mut timer) = store
+        .load_timer()
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+    else {
+        return Ok(TimerStatus::default());
+    };
+    let now = chrono::Local::now().naive_local();
+    timer.pause(now);
+    store
+        .save_timer(Some(&timer))
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    Ok(timer.status(now))
+}
+
+/// Stop the stopwatch and add the time it ran as sessions to the days it covered
+#[server]
+pub async fn stop_timer() -> Result<TimerStatus, ServerFnError> {
+    store()
+        .stop_timer(chrono::Local::now().naive_local())
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    Ok(TimerStatus::default())
+}
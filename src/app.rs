@@ -1,11 +1,11 @@
 use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
 use leptos_router::{
-    components::{Route, Router, Routes},
-    StaticSegment,
+    components::{Route, Router, Routes, A},
+    ParamSegment, StaticSegment,
 };
 
-use crate::pages::home;
+use crate::pages::{day, export, home, invoices, month, not_found, settings};
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
@@ -41,9 +41,23 @@ pub fn App() -> impl IntoView {
 
         // content for this welcome page
         <Router>
+            <nav class="top-nav">
+                <A href="/">"Calendar"</A>
+                <A href="/invoices">"Invoices"</A>
+                <A href="/export">"Import/Export"</A>
+                <A href="/settings">"Settings"</A>
+            </nav>
             <main>
-                <Routes fallback=|| "Page not found.".into_view()>
+                <Routes fallback=not_found::NotFound>
                     <Route path=StaticSegment("") view=home::HomePage/>
+                    <Route
+                        path=(StaticSegment("calendar"), ParamSegment("year"), ParamSegment("month"))
+                        view=month::MonthPage
+                    />
+                    <Route path=(StaticSegment("day"), ParamSegment("date")) view=day::DayPage/>
+                    <Route path=StaticSegment("invoices") view=invoices::InvoicesPage/>
+                    <Route path=StaticSegment("export") view=export::ExportPage/>
+                    <Route path=StaticSegment("settings") view=settings::SettingsPage/>
                 </Routes>
             </main>
         </Router>
@@ -0,0 +1,76 @@
+use crate::models::friday_cycle::FridayCycle;
+use crate::models::{user_settings::UserSettings, work_schedule::WorkSchedule};
+use crate::store::{StoreError, TimeStore};
+use axum::extract::FromRef;
+use axum::http::{HeaderMap, HeaderName};
+use chrono::NaiveDate;
+use leptos::prelude::LeptosOptions;
+use std::sync::Arc;
+
+/// Shared server state, available to server functions through context
+#[derive(Clone)]
+pub struct AppState {
+    pub leptos_options: LeptosOptions,
+    pub store: Arc<dyn TimeStore>,
+    pub friday_cycle: FridayCycle,
+    /// Request header naming the user, set by an authenticating reverse proxy
+    pub user_header: Option<HeaderName>,
+}
+
+impl AppState {
+    /// User making a request, empty when no user header is configured or sent
+    pub fn user(&self, headers: &HeaderMap) -> String {
+        self.user_header
+            .as_ref()
+            .and_then(|name| headers.get(name))
+            .and_then(|value| value.to_str().ok())
+            .map(|user| user.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Saved settings of `user`, or the server defaults if they saved none yet
+    pub fn user_settings(&self, user: &str) -> Result<UserSettings, StoreError> {
+        let settings = self.store.load_settings(user)?;
+        Ok(settings.unwrap_or_else(|| UserSettings {
+            work_schedule: WorkSchedule::with_friday_cycle(self.friday_cycle),
+            ..Default::default()
+        }))
+    }
+}
+
+impl FromRef<AppState> for LeptosOptions {
+    fn from_ref(state: &AppState) -> Self {
+        state.leptos_options.clone()
+    }
+}
+
+/// Read the working Friday cycle from `HOURS_COUNTER_FRIDAY_ANCHOR` (a working Friday, as
+/// `YYYY-MM-DD`) and `HOURS_COUNTER_FRIDAY_INTERVAL` (weeks between working Fridays)
+pub fn friday_cycle_from_env() -> Result<FridayCycle, String> {
+    let default = FridayCycle::default();
+    let anchor = match std::env::var("HOURS_COUNTER_FRIDAY_ANCHOR") {
+        Ok(anchor) => NaiveDate::parse_from_str(&anchor, "%Y-%m-%d")
+            .map_err(|e| format!("invalid HOURS_COUNTER_FRIDAY_ANCHOR `{anchor}`: {e}"))?,
+        Err(_) => default.anchor(),
+    };
+    let interval_weeks = match std::env::var("HOURS_COUNTER_FRIDAY_INTERVAL") {
+        Ok(interval) => interval
+            .parse()
+            .map_err(|e| format!("invalid HOURS_COUNTER_FRIDAY_INTERVAL `{interval}`: {e}"))?,
+        Err(_) => default.interval_weeks(),
+    };
+
+    FridayCycle::new(anchor, interval_weeks)
+        .ok_or_else(|| format!("HOURS_COUNTER_FRIDAY_ANCHOR {anchor} is not a Friday"))
+}
+
+/// Read the request header naming the user from `HOURS_COUNTER_USER_HEADER`, e.g.
+/// `X-Forwarded-User`; without it everyone shares the settings of the empty user name
+pub fn user_header_from_env() -> Result<Option<HeaderName>, String> {
+    match std::env::var("HOURS_COUNTER_USER_HEADER") {
+        Ok(name) => HeaderName::try_from(name.as_str())
+            .map(Some)
+            .map_err(|e| format!("invalid HOURS_COUNTER_USER_HEADER `{name}`: {e}")),
+        Err(_) => Ok(None),
+    }
+}
@@ -1 +1,5 @@
+pub mod file_input;
+pub mod meeting_import;
+pub mod stopwatch;
 pub mod time_input_modal;
+pub mod vacation_summary;
@@ -0,0 +1,23 @@
+use leptos::ev::Event;
+
+/// Read the file chosen in a file input as text and pass it on; only the browser reads files
+pub fn read_chosen_file(ev: &Event, on_text: impl FnOnce(String) + 'static) {
+    #[cfg(feature = "hydrate")]
+    {
+        use leptos::prelude::event_target;
+        use leptos::task::spawn_local;
+        use wasm_bindgen_futures::JsFuture;
+
+        let input = event_target::<web_sys::HtmlInputElement>(ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        spawn_local(async move {
+            if let Ok(text) = JsFuture::from(file.text()).await {
+                on_text(text.as_string().unwrap_or_default());
+            }
+        });
+    }
+    #[cfg(not(feature = "hydrate"))]
+    let _ = (ev, on_text);
+}
@@ -0,0 +1,121 @@
+use crate::api::suggest_hours;
+use crate::components::file_input::read_chosen_file;
+use crate::models::{calendar_state::CalendarDate, day_data::DayData};
+use chrono::{prelude::*, Months};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Read the meetings of an iCalendar file and suggest their busy time as hours to log
+#[component]
+pub fn MeetingImport(
+    /// Month shown, whose days are suggested unless another range is picked
+    #[prop(into)]
+    month: Signal<(i32, u32)>,
+    /// Called with the busy time of each day, or no days to clear the suggestions
+    #[prop(into)]
+    on_suggest: Callback<Vec<(CalendarDate, DayData)>>,
+) -> impl IntoView {
+    let content = RwSignal::new(None::<String>);
+    let from = RwSignal::new(None::<NaiveDate>);
+    let to = RwSignal::new(None::<NaiveDate>);
+    let email = RwSignal::new(String::new());
+    let status = RwSignal::new(None::<Result<String, String>>);
+    let pending = RwSignal::new(false);
+
+    let month_start = move || {
+        let (year, month) = month.get();
+        NaiveDate::from_ymd_opt(year, month, 1)
+    };
+    let range = move || {
+        let start = from.get().or_else(month_start)?;
+        let end = to.get().or_else(|| {
+            month_start()?
+                .checked_add_months(Months::new(1))?
+                .pred_opt()
+        })?;
+        Some((start, end))
+    };
+
+    let load_file = move |ev: leptos::ev::Event| {
+        read_chosen_file(&ev, move |text| content.set(Some(text)));
+    };
+    let suggest = move |_| {
+        let Some(text) = content.get() else {
+            status.set(Some(Err("Choose a calendar file first.".to_string())));
+            return;
+        };
+        let Some((from, to)) = range() else {
+            return;
+        };
+        let email = email.get().trim().to_string();
+        pending.set(true);
+        spawn_local(async move {
+            let result = match suggest_hours(text, from, to, email).await {
+                Ok(busy) => {
+                    let mut message = match busy.days.len() {
+                        0 => "No meetings found in the range.".to_string(),
+                        1 => "Busy time found on 1 day, open it to use the suggestion.".to_string(),
+                        days => format!(
+                            "Busy time found on {days} days, open them to use the suggestions."
+                        ),
+                    };
+                    for reason in busy.skipped {
+                        message.push_str(&format!(" Skipped: {reason}."));
+                    }
+                    on_suggest.run(busy.days);
+                    Ok(message)
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            status.set(Some(result));
+            pending.set(false);
+        });
+    };
+    let clear = move |_| {
+        on_suggest.run(Vec::new());
+        status.set(None);
+    };
+
+    view! {
+        <details class="meeting-import">
+            <summary>"Suggest hours from meetings"</summary>
+            <div class="settings-grid">
+                <label>"Calendar file (.ics)"</label>
+                <input type="file" accept=".ics,text/calendar" on:change=load_file/>
+                <label>"From"</label>
+                <input
+                    type="date"
+                    prop:value=move || range().map(|(from, _)| from.to_string()).unwrap_or_default()
+                    on:change=move |ev| from.set(event_target_value(&ev).parse().ok())
+                />
+                <label>"To"</label>
+                <input
+                    type="date"
+                    prop:value=move || range().map(|(_, to)| to.to_string()).unwrap_or_default()
+                    on:change=move |ev| to.set(event_target_value(&ev).parse().ok())
+                />
+                <label>"Your e-mail in the invitations"</label>
+                <input
+                    type="email"
+                    placeholder="Leaves out declined meetings"
+                    prop:value=move || email.get()
+                    on:input=move |ev| email.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="modal-buttons">
+                <button on:click=clear>"Clear suggestions"</button>
+                <button disabled=move || pending.get() on:click=suggest>
+                    "Suggest hours"
+                </button>
+            </div>
+            {move || {
+                status
+                    .get()
+                    .map(|status| match status {
+                        Ok(message) => view! { <p class="saved">{message}</p> }.into_any(),
+                        Err(message) => view! { <p class="error">{message}</p> }.into_any(),
+                    })
+            }}
+        </details>
+    }
+}
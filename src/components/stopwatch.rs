@@ -0,0 +1,108 @@
+use crate::api::{get_timer_status, PauseTimer, StartTimer, StopTimer};
+use chrono::prelude::*;
+use leptos::prelude::*;
+use std::time::Duration;
+
+/// Start, pause and stop a timer whose time is logged on the days it ran
+#[component]
+pub fn Stopwatch(
+    /// Called after the timer was stopped and its time logged
+    #[prop(optional, into)]
+    on_stop: Option<Callback<()>>,
+) -> impl IntoView {
+    let start_timer = ServerAction::<StartTimer>::new();
+    let pause_timer = ServerAction::<PauseTimer>::new();
+    let stop_timer = ServerAction::<StopTimer>::new();
+    let status = Resource::new(
+        move || {
+            (
+                start_timer.version().get(),
+                pause_timer.version().get(),
+                stop_timer.version().get(),
+            )
+        },
+        |_| get_timer_status(),
+    );
+    if let Some(on_stop) = on_stop {
+        Effect::watch(
+            move || stop_timer.version().get(),
+            move |_, _, _| on_stop.run(()),
+            false,
+        );
+    }
+
+    // The status is taken on the server; a running timer keeps counting from when it arrived
+    let received_at = Memo::new(move |_| {
+        status.track();
+        Utc::now()
+    });
+    let (now, set_now) = signal(Utc::now());
+    Effect::new(move |_| {
+        if let Ok(handle) =
+            set_interval_with_handle(move || set_now.set(Utc::now()), Duration::from_secs(1))
+        {
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    view! {
+        <div class="stopwatch">
+            <Transition fallback=|| ()>
+                {move || {
+                    status
+                        .get()
+                        .map(|status| match status {
+                            Ok(status) => {
+                                let running = status.running;
+                                let elapsed = move || {
+                                    let seconds = if running {
+                                        status.elapsed_seconds
+                                            + (now.get() - received_at.get()).num_seconds().max(0)
+                                    } else {
+                                        status.elapsed_seconds
+                                    };
+                                    format!(
+                                        "{}:{:02}:{:02}",
+                                        seconds / 3600,
+                                        seconds % 3600 / 60,
+                                        seconds % 60,
+                                    )
+                                };
+                                let can_stop = running || status.elapsed_seconds > 0;
+                                view! {
+                                    <span class="elapsed" class:running=running>
+                                        {elapsed}
+                                    </span>
+                                    {if running {
+                                        view! {
+                                            <button on:click=move |_| {
+                                                pause_timer.dispatch(PauseTimer {});
+                                            }>"Pause"</button>
+                                        }
+                                            .into_any()
+                                    } else {
+                                        view! {
+                                            <button on:click=move |_| {
+                                                start_timer.dispatch(StartTimer {});
+                                            }>"Start"</button>
+                                        }
+                                            .into_any()
+                                    }}
+                                    <button
+                                        disabled=!can_stop
+                                        on:click=move |_| {
+                                            stop_timer.dispatch(StopTimer {});
+                                        }
+                                    >
+                                        "Stop"
+                                    </button>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
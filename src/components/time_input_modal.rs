@@ -1,31 +1,130 @@
-use crate::models::{calendar_state::CalendarState, calendar_state::CalendarDate};
+use crate::models::{
+    calendar_state::CalendarDate,
+    day_data::{DayData, EntryKind, Session},
+    project::Project,
+    work_span::{BreakInterval, WorkSpan},
+};
 use chrono::prelude::*;
-use leptos::*;
 use leptos::prelude::*;
 
+/// A session as it is being edited, possibly with only one of the clock times entered
+#[derive(Clone, Debug, Default)]
+struct SessionDraft {
+    hours: u32,
+    minutes: u32,
+    clock_in: Option<NaiveTime>,
+    clock_out: Option<NaiveTime>,
+    breaks: Vec<BreakInterval>,
+    note: String,
+    project: Option<u64>,
+    /// Comma separated tags
+    tags: String,
+}
+
+impl SessionDraft {
+    fn from_session(session: &Session) -> Self {
+        Self {
+            hours: session.hours,
+            minutes: session.minutes,
+            clock_in: session.span.as_ref().map(|span| span.start),
+            clock_out: session.span.as_ref().map(|span| span.end),
+            breaks: session
+                .span
+                .as_ref()
+                .map(|span| span.breaks.clone())
+                .unwrap_or_default(),
+            note: session.note.clone(),
+            project: session.project,
+            tags: session.tags.join(", "),
+        }
+    }
+
+    /// Span of the session once both clock times are entered
+    fn span(&self) -> Option<WorkSpan> {
+        Some(WorkSpan {
+            start: self.clock_in?,
+            end: self.clock_out?,
+            breaks: self.breaks.clone(),
+        })
+    }
+
+    fn to_session(&self) -> Result<Session, &'static str> {
+        let session = match self.span() {
+            Some(span) => {
+                span.validate()?;
+                Session::from_span(span)
+            }
+            None => Session::new(self.hours, self.minutes),
+        };
+        Ok(session
+            .with_note(self.note.trim())
+            .with_project(self.project)
+            .with_tags(self.tags.split(',')))
+    }
+}
+
 #[component]
 pub fn TimeInputModal(
-    state: CalendarState,
     selected_date: CalendarDate,
-    on_save: fn(u32, u32) -> (),
-    on_close: fn() -> (),
+    current_data: DayData,
+    is_working_friday: bool,
+    projects: Vec<Project>,
+    /// Busy time read from a calendar file, offered to replace the sessions
+    suggestion: Option<DayData>,
+    #[prop(into)] on_save: Callback<DayData>,
+    #[prop(into)] on_toggle_working_friday: Callback<bool>,
+    #[prop(into)] on_clear: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+    /// Why the server refused the last save or clear
+    #[prop(into)]
+    server_error: Signal<Option<String>>,
 ) -> impl IntoView {
     let (year, month, day) = selected_date;
-    let hours_data = state.get_hours_data();
-    let working_fridays = state.get_working_fridays();
 
-    let current_data = hours_data
-        .get()
-        .get(&selected_date)
-        .cloned()
-        .unwrap_or_default();
-
-    let (input_hours, set_input_hours) = create_signal(current_data.hours);
-    let (input_minutes, set_input_minutes) = create_signal(current_data.minutes);
+    let (input_kind, set_input_kind) = signal(current_data.kind());
+    let mut drafts = current_data
+        .sessions()
+        .iter()
+        .map(SessionDraft::from_session)
+        .collect::<Vec<_>>();
+    if drafts.is_empty() {
+        drafts.push(SessionDraft::default());
+    }
+    let sessions = RwSignal::new(drafts);
+    let session_count = Memo::new(move |_| sessions.with(Vec::len));
+    let (form_error, set_form_error) = signal::<Option<&'static str>>(None);
 
     let current_date = Local.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap();
     let is_friday = current_date.weekday() == Weekday::Fri;
-    let is_working = working_fridays.get().contains(&selected_date);
+
+    let save = move |_| {
+        let kind = input_kind.get();
+        if kind.is_absence() {
+            on_save.run(DayData::default().with_kind(kind));
+            return;
+        }
+        match sessions
+            .get()
+            .iter()
+            .map(SessionDraft::to_session)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(sessions) => on_save.run(DayData::from_sessions(sessions).with_kind(kind)),
+            Err(message) => set_form_error.set(Some(message)),
+        }
+    };
+    let total = move || {
+        let minutes = sessions.with(|sessions| {
+            sessions
+                .iter()
+                .map(|session| match session.span() {
+                    Some(span) => span.worked_minutes(),
+                    None => session.hours * 60 + session.minutes,
+                })
+                .sum::<u32>()
+        });
+        format!("Total: {}:{:02}", minutes / 60, minutes % 60)
+    };
 
     view! {
         <div class="modal">
@@ -38,34 +137,64 @@ pub fn TimeInputModal(
                         year,
                     )}
                 </h3>
+                {suggestion
+                    .map(|suggestion| {
+                        let label = format!("From your calendar: {}", suggestion.format());
+                        let use_suggestion = move |_| {
+                            sessions
+                                .set(
+                                    suggestion
+                                        .sessions()
+                                        .iter()
+                                        .map(SessionDraft::from_session)
+                                        .collect(),
+                                );
+                            set_input_kind.set(EntryKind::Work);
+                            set_form_error.set(None);
+                        };
+                        view! {
+                            <div class="suggestion">
+                                <span>{label}</span>
+                                <button on:click=use_suggestion>"Use suggestion"</button>
+                            </div>
+                        }
+                    })}
                 <div class="time-inputs">
-                    <div class="hours-input">
-                        <label>"Hours:"</label>
-                        <input
-                            type="number"
-                            min="0"
-                            max="23"
-                            prop:value=input_hours
-                            on:input=move |ev| {
-                                if let Ok(hours) = event_target_value(&ev).parse::<u32>() {
-                                    set_input_hours.set(hours);
+                    <div class="kind-input">
+                        <label>"Day:"</label>
+                        <select
+                            prop:value=move || format!("{:?}", input_kind.get())
+                            on:change=move |ev| {
+                                let value = event_target_value(&ev);
+                                if let Some(kind) = EntryKind::ALL
+                                    .into_iter()
+                                    .find(|kind| format!("{kind:?}") == value)
+                                {
+                                    set_input_kind.set(kind);
                                 }
                             }
-                        />
+                        >
+                            {EntryKind::ALL
+                                .into_iter()
+                                .map(|kind| {
+                                    view! { <option value=format!("{kind:?}")>{kind.label()}</option> }
+                                })
+                                .collect::<Vec<_>>()}
+                        </select>
                     </div>
-                    <div class="minutes-input">
-                        <label>"Minutes:"</label>
-                        <input
-                            type="number"
-                            min="0"
-                            max="59"
-                            prop:value=input_minutes
-                            on:input=move |ev| {
-                                if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
-                                    set_input_minutes.set(minutes);
-                                }
-                            }
-                        />
+                    <div class="sessions" class:hidden=move || input_kind.get().is_absence()>
+                        {move || {
+                            (0..session_count.get())
+                                .map(|index| {
+                                    view! { <SessionRow sessions index projects=projects.clone()/> }
+                                })
+                                .collect::<Vec<_>>()
+                        }}
+                        <button on:click=move |_| {
+                            sessions.update(|sessions| sessions.push(SessionDraft::default()))
+                        }>"Add session"</button>
+                        <p class="session-total">{total}</p>
+                        {move || form_error.get().map(|message| view! { <p class="error">{message}</p> })}
                     </div>
                     <div
                         class="working-friday-toggle"
@@ -74,10 +203,10 @@ pub fn TimeInputModal(
                         <label class="working-friday-label">
                             <input
                                 type="checkbox"
-                                prop:checked=is_working
-                                on:change=move |_| {
+                                prop:checked=is_working_friday
+                                on:change=move |ev| {
                                     if is_friday {
-                                        state.toggle_working_friday(selected_date);
+                                        on_toggle_working_friday.run(event_target_checked(&ev));
                                     }
                                 }
                             />
@@ -85,17 +214,200 @@ pub fn TimeInputModal(
                         </label>
                     </div>
                 </div>
+                {move || server_error.get().map(|message| view! { <p class="error">{message}</p> })}
                 <div class="modal-buttons">
-                    <button on:click=move |_| on_close()>"Cancel"</button>
-                    <button on:click=move |_| {
-                        on_save(input_hours.get(), input_minutes.get())
-                    }>"Save"</button>
+                    <button on:click=move |_| on_clear.run(())>"Clear"</button>
+                    <button on:click=move |_| on_close.run(())>"Cancel"</button>
+                    <button on:click=save>"Save"</button>
                 </div>
             </div>
         </div>
     }
 }
 
+/// Inputs of one session: a duration, or clock times with breaks, a project, tags and a note
+#[component]
+fn SessionRow(
+    sessions: RwSignal<Vec<SessionDraft>>,
+    index: usize,
+    projects: Vec<Project>,
+) -> impl IntoView {
+    let field = move |read: fn(&SessionDraft) -> String| session_field(sessions, index, read);
+    let update = move |change: &dyn Fn(&mut SessionDraft)| {
+        sessions.update(|sessions| {
+            if let Some(session) = sessions.get_mut(index) {
+                change(session);
+            }
+        })
+    };
+    let has_span = Memo::new(move |_| {
+        sessions.with(|sessions| sessions.get(index).is_some_and(|session| session.span().is_some()))
+    });
+    let break_count = Memo::new(move |_| {
+        sessions.with(|sessions| sessions.get(index).map_or(0, |session| session.breaks.len()))
+    });
+    // Archived projects stay available to the sessions already billed to them
+    let selected_project = sessions.with_untracked(|sessions| {
+        sessions.get(index).and_then(|session| session.project)
+    });
+    let project_options = projects
+        .into_iter()
+        .filter(|project| project.active || Some(project.id) == selected_project)
+        .map(|project| view! { <option value=project.id.to_string()>{project.name}</option> })
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="session">
+            <div class="span-inputs">
+                <label>"Clock in:"</label>
+                <input
+                    type="time"
+                    prop:value=field(|session| format_time(session.clock_in))
+                    on:change=move |ev| {
+                        let time = parse_time(&event_target_value(&ev));
+                        update(&|session| session.clock_in = time);
+                    }
+                />
+                <label>"Clock out:"</label>
+                <input
+                    type="time"
+                    prop:value=field(|session| format_time(session.clock_out))
+                    on:change=move |ev| {
+                        let time = parse_time(&event_target_value(&ev));
+                        update(&|session| session.clock_out = time);
+                    }
+                />
+            </div>
+            {move || {
+                (0..break_count.get())
+                    .map(|break_index| {
+                        let set_break_time = move |ev, set_end: bool| {
+                            if let Some(time) = parse_time(&event_target_value(&ev)) {
+                                update(&|session| {
+                                    let interval = &mut session.breaks[break_index];
+                                    if set_end {
+                                        interval.end = time;
+                                    } else {
+                                        interval.start = time;
+                                    }
+                                });
+                            }
+                        };
+                        let break_time = move |read: fn(&BreakInterval) -> NaiveTime| {
+                            session_field(
+                                sessions,
+                                index,
+                                move |session| format_time(session.breaks.get(break_index).map(read)),
+                            )
+                        };
+                        view! {
+                            <div class="break-input">
+                                <label>"Break:"</label>
+                                <input
+                                    type="time"
+                                    prop:value=break_time(|interval| interval.start)
+                                    on:change=move |ev| set_break_time(ev, false)
+                                />
+                                <input
+                                    type="time"
+                                    prop:value=break_time(|interval| interval.end)
+                                    on:change=move |ev| set_break_time(ev, true)
+                                />
+                                <button on:click=move |_| {
+                                    update(&|session| {
+                                        session.breaks.remove(break_index);
+                                    })
+                                }>"Remove"</button>
+                            </div>
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }}
+            <div class="duration-inputs" class:hidden=move || has_span.get()>
+                <label>"Hours:"</label>
+                <input
+                    type="number"
+                    min="0"
+                    max="23"
+                    prop:value=field(|session| session.hours.to_string())
+                    on:input=move |ev| {
+                        if let Ok(hours) = event_target_value(&ev).parse::<u32>() {
+                            update(&|session| session.hours = hours);
+                        }
+                    }
+                />
+                <label>"Minutes:"</label>
+                <input
+                    type="number"
+                    min="0"
+                    max="59"
+                    prop:value=field(|session| session.minutes.to_string())
+                    on:input=move |ev| {
+                        if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
+                            update(&|session| session.minutes = minutes);
+                        }
+                    }
+                />
+            </div>
+            <div class="project-input">
+                <label>"Project:"</label>
+                <select
+                    prop:value=field(|session| {
+                        session.project.map(|id| id.to_string()).unwrap_or_default()
+                    })
+                    on:change=move |ev| {
+                        let project = event_target_value(&ev).parse::<u64>().ok();
+                        update(&|session| session.project = project);
+                    }
+                >
+                    <option value="">"No project"</option>
+                    {project_options}
+                </select>
+            </div>
+            <input
+                type="text"
+                placeholder="Tags, comma separated"
+                prop:value=field(|session| session.tags.clone())
+                on:input=move |ev| {
+                    let tags = event_target_value(&ev);
+                    update(&|session| session.tags = tags.clone());
+                }
+            />
+            <input
+                type="text"
+                placeholder="Note, e.g. on site"
+                prop:value=field(|session| session.note.clone())
+                on:input=move |ev| {
+                    let note = event_target_value(&ev);
+                    update(&|session| session.note = note.clone());
+                }
+            />
+            <div class="session-buttons">
+                <button on:click=move |_| {
+                    update(&|session| {
+                        session
+                            .breaks
+                            .push(BreakInterval {
+                                start: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+                                end: NaiveTime::from_hms_opt(12, 30, 0).unwrap(),
+                            })
+                    })
+                }>"Add break"</button>
+                <button on:click=move |_| {
+                    sessions
+                        .update(|sessions| {
+                            if sessions.len() > 1 {
+                                sessions.remove(index);
+                            } else {
+                                sessions[0] = SessionDraft::default();
+                            }
+                        })
+                }>"Remove session"</button>
+            </div>
+        </div>
+    }
+}
+
 fn get_month_name(month: u32) -> &'static str {
     match month {
         1 => "January",
@@ -114,3 +426,22 @@ fn get_month_name(month: u32) -> &'static str {
     }
 }
 
+/// Reactive text of a field of the session at `index`
+fn session_field(
+    sessions: RwSignal<Vec<SessionDraft>>,
+    index: usize,
+    read: impl Fn(&SessionDraft) -> String + Send + Sync + 'static,
+) -> impl Fn() -> String + Send + Sync + 'static {
+    move || {
+        sessions.with(|sessions| sessions.get(index).map(&read).unwrap_or_default())
+    }
+}
+
+fn format_time(time: Option<NaiveTime>) -> String {
+    time.map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
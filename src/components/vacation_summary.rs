@@ -0,0 +1,48 @@
+use crate::api::load_vacation_ledger;
+use leptos::prelude::*;
+
+/// Vacation days of a year, reloaded whenever `refresh` changes
+#[component]
+pub fn VacationSummary(year: i32, #[prop(into)] refresh: Signal<usize>) -> impl IntoView {
+    let ledger = Resource::new(move || refresh.get(), move |_| load_vacation_ledger(year));
+
+    view! {
+        <div class="vacation-summary">
+            <h3>{format!("Vacation {year}")}</h3>
+            <Transition fallback=|| view! { <p>"Loading..."</p> }>
+                {move || {
+                    ledger
+                        .get()
+                        .map(|ledger| match ledger {
+                            Ok(ledger) => {
+                                view! {
+                                    <dl>
+                                        <dt>"Entitlement"</dt>
+                                        <dd>{format_days(ledger.entitlement)}</dd>
+                                        <dt>"Carried over"</dt>
+                                        <dd>{format_days(ledger.carried_over)}</dd>
+                                        <dt>"Taken"</dt>
+                                        <dd>{format_days(ledger.used)}</dd>
+                                        <dt>"Remaining"</dt>
+                                        <dd class="remaining">{format_days(ledger.remaining())}</dd>
+                                    </dl>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+fn format_days(days: f32) -> String {
+    if days == 1.0 {
+        "1 day".to_string()
+    } else if days.fract() == 0.0 {
+        format!("{days:.0} days")
+    } else {
+        format!("{days:.1} days")
+    }
+}
@@ -0,0 +1,324 @@
+use crate::app_state::AppState;
+use crate::models::balance::{month_balance, DayBalance, MonthBalance};
+use crate::models::calendar_state::CalendarDate;
+use crate::models::day_data::{DayData, EntryKind};
+use crate::models::export::{export_rows, ExportOptions, ExportRow};
+use crate::models::work_calendar::WorkCalendar;
+use crate::store::StoreError;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::{Datelike, Local, Utc, Weekday};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+mod csv_export;
+mod ics_export;
+mod invoice;
+mod pdf;
+mod timesheet;
+mod xlsx_export;
+
+/// Routes serving documents generated from the stored data
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/invoices/:file", get(invoice_document))
+        .route("/export/csv", get(csv_document))
+        .route("/export/xlsx", get(xlsx_document))
+        .route("/export/ics", get(ics_document))
+        .route("/timesheet/:file", get(timesheet_document))
+}
+
+/// An issued invoice, as `<number>.html` or `<number>.pdf`
+async fn invoice_document(State(state): State<AppState>, Path(file): Path<String>) -> Response {
+    let Some((number, extension)) = file.rsplit_once('.') else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let invoice = match state.store.list_invoices() {
+        Ok(invoices) => invoices
+            .into_iter()
+            .find(|invoice| invoice.number() == number),
+        Err(e) => return server_error(e),
+    };
+    match (invoice, extension) {
+        (Some(invoice), "html") => Html(invoice::html(&invoice)).into_response(),
+        (Some(invoice), "pdf") => (
+            [(header::CONTENT_TYPE, "application/pdf")],
+            invoice::pdf(&invoice),
+        )
+            .into_response(),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Logged days of a date range as a CSV download
+async fn csv_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(options): Query<ExportOptions>,
+) -> Response {
+    if options.from > options.to {
+        return (StatusCode::BAD_REQUEST, "the range ends before it starts").into_response();
+    }
+    let rows = match rows_to_export(&state, &state.user(&headers), &options) {
+        Ok(rows) => rows,
+        Err(e) => return server_error(e),
+    };
+    match csv_export::csv(&rows, &options) {
+        Ok(body) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, attachment(&options, "csv")),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => server_error(e),
+    }
+}
+
+/// Logged days of a date range as an iCalendar download
+async fn ics_document(
+    State(state): State<AppState>,
+    Query(options): Query<ExportOptions>,
+) -> Response {
+    if options.from > options.to {
+        return (StatusCode::BAD_REQUEST, "the range ends before it starts").into_response();
+    }
+    let from = (
+        options.from.year(),
+        options.from.month(),
+        options.from.day(),
+    );
+    let to = (options.to.year(), options.to.month(), options.to.day());
+    let (entries, projects) = match state
+        .store
+        .get_range(from, to)
+        .and_then(|entries| Ok((entries, state.store.list_projects()?)))
+    {
+        Ok(loaded) => loaded,
+        Err(e) => return server_error(e),
+    };
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/calendar; charset=utf-8".to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, attachment(&options, "ics")),
+        ],
+        ics_export::ics(&entries, &projects, Utc::now()),
+    )
+        .into_response()
+}
+
+/// Month of a timesheet, e.g. `?year=2026&month=9`
+#[derive(Deserialize)]
+struct MonthQuery {
+    year: i32,
+    month: u32,
+}
+
+/// Every day of a month with what was logged and what the work calendar expected
+struct Timesheet {
+    year: i32,
+    month: u32,
+    employee: String,
+    balance: MonthBalance,
+    entries: HashMap<CalendarDate, DayData>,
+    work_calendar: WorkCalendar,
+}
+
+/// Timesheet of a month as an Excel workbook download
+async fn xlsx_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<MonthQuery>,
+) -> Response {
+    if !valid_month(query.year, query.month) {
+        return (StatusCode::BAD_REQUEST, "no such month").into_response();
+    }
+    let timesheet = match load_timesheet(&state, &state.user(&headers), query.year, query.month) {
+        Ok(timesheet) => timesheet,
+        Err(e) => return server_error(e),
+    };
+    match xlsx_export::xlsx(&timesheet) {
+        Ok(body) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"timesheet-{:04}-{:02}.xlsx\"",
+                        query.year, query.month
+                    ),
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => server_error(e),
+    }
+}
+
+/// Timesheet of a month to print and sign, as `<year>-<month>.html` or `<year>-<month>.pdf`
+async fn timesheet_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(file): Path<String>,
+) -> Response {
+    let month = file
+        .rsplit_once('.')
+        .and_then(|(period, extension)| {
+            let (year, month) = period.split_once('-')?;
+            Some((
+                year.parse::<i32>().ok()?,
+                month.parse::<u32>().ok()?,
+                extension,
+            ))
+        })
+        .filter(|(year, month, _)| valid_month(*year, *month));
+    let Some((year, month, extension)) = month else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !matches!(extension, "html" | "pdf") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let timesheet = match load_timesheet(&state, &state.user(&headers), year, month) {
+        Ok(timesheet) => timesheet,
+        Err(e) => return server_error(e),
+    };
+    if extension == "html" {
+        Html(timesheet::html(&timesheet)).into_response()
+    } else {
+        (
+            [(header::CONTENT_TYPE, "application/pdf")],
+            timesheet::pdf(&timesheet),
+        )
+            .into_response()
+    }
+}
+
+/// Whether a month can be put on a timesheet, spreadsheets counting dates from 1900
+fn valid_month(year: i32, month: u32) -> bool {
+    (1..=12).contains(&month) && (1900..=9999).contains(&year)
+}
+
+fn load_timesheet(
+    state: &AppState,
+    user: &str,
+    year: i32,
+    month: u32,
+) -> Result<Timesheet, StoreError> {
+    let settings = state.user_settings(user)?;
+    let entries = state
+        .store
+        .get_range((year, month, 1), (year, month, 31))?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let friday_overrides = state
+        .store
+        .list_friday_overrides((year, month, 1), (year, month, 31))?;
+    let work_calendar = WorkCalendar::from_settings(&settings, friday_overrides);
+    let balance = month_balance(
+        year,
+        month,
+        &entries,
+        &work_calendar,
+        Local::now().date_naive(),
+    );
+    Ok(Timesheet {
+        year,
+        month,
+        employee: settings.employee_name,
+        balance,
+        entries,
+        work_calendar,
+    })
+}
+
+/// Background of a day's row as RGB, in the order the calendar grid picks its day classes
+fn day_shade(day: &DayBalance, holiday: bool) -> Option<u32> {
+    const WEEKEND: u32 = 0xF2DCDB;
+    const NON_WORKING_FRIDAY: u32 = 0xE4E4F0;
+    const DAY_OFF: u32 = 0xDDEBE3;
+    const HOLIDAY: u32 = 0xFCE4C4;
+    const VACATION: u32 = 0xD5E8F0;
+    const SICK_LEAVE: u32 = 0xF2D0DA;
+    const UNPAID_LEAVE: u32 = 0xE6E6E6;
+    const BUSINESS_TRIP: u32 = 0xE6D5F0;
+
+    match day.kind {
+        Some(EntryKind::Vacation | EntryKind::HalfDayVacation) => Some(VACATION),
+        Some(EntryKind::SickLeave) => Some(SICK_LEAVE),
+        Some(EntryKind::PublicHoliday) => Some(HOLIDAY),
+        Some(EntryKind::UnpaidLeave) => Some(UNPAID_LEAVE),
+        Some(EntryKind::BusinessTrip) => Some(BUSINESS_TRIP),
+        _ if holiday => Some(HOLIDAY),
+        _ if day.expected_hours > 0.0 => None,
+        _ if matches!(day.date.weekday(), Weekday::Sat | Weekday::Sun) => Some(WEEKEND),
+        _ if day.date.weekday() == Weekday::Fri => Some(NON_WORKING_FRIDAY),
+        _ => Some(DAY_OFF),
+    }
+}
+
+/// Entry type of a day, or the holiday falling on it
+fn day_type<'a>(day: &DayBalance, work_calendar: &'a WorkCalendar) -> &'a str {
+    match (day.kind, work_calendar.holiday_on(day.date)) {
+        (Some(kind), _) => kind.label(),
+        (None, Some(name)) => name,
+        (None, None) => "",
+    }
+}
+
+/// Notes of a day's sessions
+fn day_note(entries: &HashMap<CalendarDate, DayData>, day: &DayBalance) -> String {
+    entries
+        .get(&(day.date.year(), day.date.month(), day.date.day()))
+        .map(|data| {
+            data.sessions()
+                .iter()
+                .map(|session| session.note.as_str())
+                .filter(|note| !note.is_empty())
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .unwrap_or_default()
+}
+
+/// Logged days in the range of the export options
+fn rows_to_export(
+    state: &AppState,
+    user: &str,
+    options: &ExportOptions,
+) -> Result<Vec<ExportRow>, StoreError> {
+    let from = (
+        options.from.year(),
+        options.from.month(),
+        options.from.day(),
+    );
+    let to = (options.to.year(), options.to.month(), options.to.day());
+    let settings = state.user_settings(user)?;
+    let entries = state.store.get_range(from, to)?;
+    let friday_overrides = state.store.list_friday_overrides(from, to)?;
+    let work_calendar = WorkCalendar::from_settings(&settings, friday_overrides);
+    Ok(export_rows(&entries, &work_calendar))
+}
+
+/// `Content-Disposition` of a download named after the exported range
+fn attachment(options: &ExportOptions, extension: &str) -> String {
+    format!(
+        "attachment; filename=\"hours-{}-{}.{extension}\"",
+        options.from, options.to
+    )
+}
+
+fn server_error(e: impl Display) -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+}
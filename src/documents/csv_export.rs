@@ -0,0 +1,32 @@
+use crate::models::export::{ExportOptions, ExportRow};
+
+/// Logged days as a CSV file, written with the delimiter and decimal separator of the options
+pub fn csv(rows: &[ExportRow], options: &ExportOptions) -> Result<Vec<u8>, csv::Error> {
+    // Without the byte order mark Excel reads the file in the legacy code page of the system
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter.byte())
+        .from_writer("\u{feff}".as_bytes().to_vec());
+    writer.write_record([
+        "Date",
+        "Weekday",
+        "Duration",
+        "Hours",
+        "Type",
+        "Note",
+        "Expected hours",
+    ])?;
+    for row in rows {
+        writer.write_record([
+            row.date.to_string(),
+            row.date.format("%A").to_string(),
+            format!("{}:{:02}", row.minutes / 60, row.minutes % 60),
+            options.decimal.format(row.hours),
+            row.kind.label().to_string(),
+            row.note.clone(),
+            options.decimal.format(row.expected_hours),
+        ])?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
@@ -0,0 +1,157 @@
+use crate::models::calendar_state::CalendarDate;
+use crate::models::day_data::{DayData, EntryKind, Session};
+use crate::models::project::Project;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+
+/// Longest line allowed by RFC 5545, in bytes without the line break
+const LINE_LENGTH: usize = 75;
+
+/// Logged days as an iCalendar file: sessions with a recorded span become timed events, the
+/// rest of each day an all-day event
+pub fn ics(
+    entries: &[(CalendarDate, DayData)],
+    projects: &[Project],
+    stamp: DateTime<Utc>,
+) -> String {
+    let mut calendar = Calendar::default();
+    calendar.line("BEGIN:VCALENDAR");
+    calendar.line("VERSION:2.0");
+    calendar.line("PRODID:-//hours_counter//Logged hours//EN");
+    calendar.line("CALSCALE:GREGORIAN");
+    calendar.line("X-WR-CALNAME:Logged hours");
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+
+    for &((year, month, day), ref data) in entries {
+        let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+            continue;
+        };
+        let (timed, untimed): (Vec<_>, Vec<_>) = data
+            .sessions()
+            .iter()
+            .partition(|session| session.span.is_some());
+
+        if data.kind().is_absence() || !untimed.is_empty() || timed.is_empty() {
+            let logged = DayData::from_sessions(untimed.iter().copied().cloned().collect());
+            let mut summary = Vec::new();
+            if data.kind() != EntryKind::Work {
+                summary.push(data.kind().label().to_string());
+            }
+            if !data.kind().is_absence() {
+                summary.push(logged.format());
+            }
+            summary.extend(project_names(&untimed, projects));
+            calendar.line("BEGIN:VEVENT");
+            calendar.line(&format!("UID:{}-day@hours_counter", date.format("%Y%m%d")));
+            calendar.line(&format!("DTSTAMP:{stamp}"));
+            calendar.line(&format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+            calendar.line(&format!(
+                "DTEND;VALUE=DATE:{}",
+                next_day(date).format("%Y%m%d")
+            ));
+            // All-day entries should not show the whole day as busy
+            calendar.line("TRANSP:TRANSPARENT");
+            calendar.text("SUMMARY", &summary.join(" – "));
+            calendar.text("DESCRIPTION", &description(&untimed));
+            calendar.line("END:VEVENT");
+        }
+
+        for (index, session) in timed.iter().enumerate() {
+            let Some(span) = &session.span else {
+                continue;
+            };
+            let start = date.and_time(span.start);
+            // A span ending at or before its start runs past midnight
+            let end = if span.end > span.start {
+                date.and_time(span.end)
+            } else {
+                next_day(date).and_time(span.end)
+            };
+            let mut summary = vec![DayData::from_sessions(vec![(*session).clone()]).format()];
+            summary.extend(project_names(&[*session], projects));
+            calendar.line("BEGIN:VEVENT");
+            calendar.line(&format!(
+                "UID:{}-{}@hours_counter",
+                date.format("%Y%m%d"),
+                index + 1
+            ));
+            calendar.line(&format!("DTSTAMP:{stamp}"));
+            calendar.line(&format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+            calendar.line(&format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+            calendar.text("SUMMARY", &summary.join(" – "));
+            calendar.text("DESCRIPTION", &description(&[*session]));
+            calendar.line("END:VEVENT");
+        }
+    }
+
+    calendar.line("END:VCALENDAR");
+    calendar.0
+}
+
+/// Lines of an iCalendar file, folded and ended as RFC 5545 asks
+#[derive(Default)]
+struct Calendar(String);
+
+impl Calendar {
+    fn line(&mut self, line: &str) {
+        let mut length = 0;
+        for c in line.chars() {
+            // Continuation lines start with a space, which counts towards their length
+            if length + c.len_utf8() > LINE_LENGTH {
+                self.0.push_str("\r\n ");
+                length = 1;
+            }
+            self.0.push(c);
+            length += c.len_utf8();
+        }
+        self.0.push_str("\r\n");
+    }
+
+    /// A text property, skipped when empty
+    fn text(&mut self, name: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n");
+        self.line(&format!("{name}:{escaped}"));
+    }
+}
+
+fn next_day(date: NaiveDate) -> NaiveDate {
+    date.checked_add_days(Days::new(1)).unwrap_or(date)
+}
+
+/// Names of the projects of the sessions, each once
+fn project_names(sessions: &[&Session], projects: &[Project]) -> Vec<String> {
+    let mut names = Vec::new();
+    for id in sessions.iter().filter_map(|session| session.project) {
+        let name = projects
+            .iter()
+            .find(|project| project.id == id)
+            .map_or_else(|| format!("Project #{id}"), |project| project.name.clone());
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Notes and tags of the sessions, one session a line
+fn description(sessions: &[&Session]) -> String {
+    sessions
+        .iter()
+        .map(|session| {
+            let tags = session.tags.iter().map(|tag| format!("#{tag}"));
+            std::iter::once(session.note.clone())
+                .chain(tags)
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,168 @@
+use super::pdf::{self, Page, PAGE_HEIGHT, PAGE_WIDTH};
+use crate::models::billing::{format_amount, Invoice};
+use chrono::NaiveDate;
+use leptos::prelude::*;
+
+const STYLE: &str = "
+    body { font-family: sans-serif; max-width: 800px; margin: 40px auto; color: #222; }
+    .parties { display: flex; justify-content: space-between; margin: 30px 0; white-space: pre-line; }
+    table { width: 100%; border-collapse: collapse; }
+    th, td { padding: 6px; border-bottom: 1px solid #ccc; text-align: left; }
+    .number { text-align: right; }
+    .total td { font-weight: bold; border-bottom: none; }
+";
+
+/// The invoice as a standalone HTML page
+pub fn html(invoice: &Invoice) -> String {
+    let currency = invoice.currency.clone();
+    let lines = invoice
+        .lines
+        .iter()
+        .map(|line| {
+            view! {
+                <tr>
+                    <td>{line.description.clone()}</td>
+                    <td class="number">{format_minutes(line.minutes)}</td>
+                    <td class="number">{format_amount(line.rate_cents, &currency)}</td>
+                    <td class="number">{format_amount(line.amount_cents, &currency)}</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+    let page = view! {
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <title>{format!("Invoice {}", invoice.number())}</title>
+                <style>{STYLE}</style>
+            </head>
+            <body>
+                <h1>{format!("Invoice {}", invoice.number())}</h1>
+                <p>
+                    {format!("Issued: {}", invoice.issued)}
+                    <br/>
+                    {format!("Due: {}", invoice.due)}
+                    <br/>
+                    {format!("Period: {}", period_label(invoice.period))}
+                </p>
+                <div class="parties">
+                    <div>{invoice.issuer.clone()}</div>
+                    <div>
+                        <strong>"Bill to"</strong>
+                        <br/>
+                        {invoice.client.clone()}
+                    </div>
+                </div>
+                <table>
+                    <tr>
+                        <th>"Description"</th>
+                        <th class="number">"Hours"</th>
+                        <th class="number">"Rate per hour"</th>
+                        <th class="number">"Amount"</th>
+                    </tr>
+                    {lines}
+                    <tr class="total">
+                        <td>"Total"</td>
+                        <td class="number">{format_minutes(invoice.total_minutes())}</td>
+                        <td></td>
+                        <td class="number">
+                            {format_amount(invoice.total_cents(), &invoice.currency)}
+                        </td>
+                    </tr>
+                </table>
+            </body>
+        </html>
+    };
+    format!("<!DOCTYPE html>{}", page.to_html())
+}
+
+/// The invoice as a one-page PDF
+pub fn pdf(invoice: &Invoice) -> Vec<u8> {
+    const LEFT: f32 = 50.0;
+    const RIGHT: f32 = PAGE_WIDTH - 50.0;
+    const COLUMNS: [f32; 4] = [LEFT, 310.0, 380.0, 470.0];
+
+    let mut page = Page::default();
+    let mut y = PAGE_HEIGHT - 70.0;
+    page.text(
+        LEFT,
+        y,
+        20.0,
+        true,
+        &format!("Invoice {}", invoice.number()),
+    );
+    y -= 30.0;
+    for line in [
+        format!("Issued: {}", invoice.issued),
+        format!("Due: {}", invoice.due),
+        format!("Period: {}", period_label(invoice.period)),
+    ] {
+        page.text(LEFT, y, 10.0, false, &line);
+        y -= 14.0;
+    }
+
+    y -= 20.0;
+    page.text(COLUMNS[2], y, 10.0, true, "Bill to");
+    let issuer = invoice.issuer.lines().collect::<Vec<_>>();
+    let client = invoice.client.lines().collect::<Vec<_>>();
+    for (index, line) in issuer.iter().enumerate() {
+        page.text(LEFT, y - 14.0 * index as f32, 10.0, false, line);
+    }
+    for (index, line) in client.iter().enumerate() {
+        page.text(COLUMNS[2], y - 14.0 * (index + 1) as f32, 10.0, false, line);
+    }
+    y -= 14.0 * issuer.len().max(client.len() + 1) as f32 + 30.0;
+
+    for (x, heading) in COLUMNS
+        .into_iter()
+        .zip(["Description", "Hours", "Rate per hour", "Amount"])
+    {
+        page.text(x, y, 10.0, true, heading);
+    }
+    y -= 6.0;
+    page.rule(LEFT, RIGHT, y);
+    y -= 16.0;
+    for line in &invoice.lines {
+        let cells = [
+            line.description.clone(),
+            format_minutes(line.minutes),
+            format_amount(line.rate_cents, &invoice.currency),
+            format_amount(line.amount_cents, &invoice.currency),
+        ];
+        for (x, cell) in COLUMNS.into_iter().zip(&cells) {
+            page.text(x, y, 10.0, false, cell);
+        }
+        y -= 16.0;
+    }
+    page.rule(LEFT, RIGHT, y + 10.0);
+    y -= 6.0;
+    page.text(COLUMNS[0], y, 11.0, true, "Total");
+    page.text(
+        COLUMNS[1],
+        y,
+        11.0,
+        true,
+        &format_minutes(invoice.total_minutes()),
+    );
+    page.text(
+        COLUMNS[3],
+        y,
+        11.0,
+        true,
+        &format_amount(invoice.total_cents(), &invoice.currency),
+    );
+
+    pdf::render(vec![page])
+}
+
+/// Minutes as hours and minutes, e.g. `12:30`
+fn format_minutes(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Month billed, e.g. `October 2026`
+fn period_label((year, month): (i32, u32)) -> String {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .map(|date| date.format("%B %Y").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,104 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+/// Width of an A4 page in points
+pub const PAGE_WIDTH: f32 = 595.0;
+/// Height of an A4 page in points
+pub const PAGE_HEIGHT: f32 = 842.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+/// One page of text and rules, positioned in points from its bottom left corner
+pub struct Page {
+    content: Content,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            content: Content::new(),
+        }
+    }
+}
+
+impl Page {
+    /// Write a line of text starting at `(x, y)`
+    pub fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        self.content
+            .begin_text()
+            .set_font(if bold { BOLD } else { REGULAR }, size)
+            .next_line(x, y)
+            .show(Str(&win_ansi(text)))
+            .end_text();
+    }
+
+    /// Draw a horizontal rule from `x1` to `x2` at height `y`
+    pub fn rule(&mut self, x1: f32, x2: f32, y: f32) {
+        self.content
+            .set_line_width(0.5)
+            .move_to(x1, y)
+            .line_to(x2, y)
+            .stroke();
+    }
+}
+
+/// Write pages into a PDF file using the standard Helvetica fonts
+pub fn render(pages: Vec<Page>) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    // Every page takes two ids, one for the page and one for its content
+    let page_ids = (0..pages.len())
+        .map(|index| Ref::new(5 + 2 * index as i32))
+        .collect::<Vec<_>>();
+
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(page_ids.len() as i32);
+    pdf.type1_font(regular_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (page, page_id) in pages.into_iter().zip(page_ids) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut writer = pdf.page(page_id);
+        writer
+            .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        writer
+            .resources()
+            .fonts()
+            .pair(REGULAR, regular_id)
+            .pair(BOLD, bold_id);
+        writer.finish();
+        pdf.stream(content_id, &page.content.finish());
+    }
+
+    pdf.finish()
+}
+
+/// Text in the WinAnsi encoding of the standard fonts, with `?` for characters it lacks
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
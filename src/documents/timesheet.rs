@@ -0,0 +1,180 @@
+use super::pdf::{self, Page, PAGE_HEIGHT, PAGE_WIDTH};
+use super::{day_note, day_shade, day_type, Timesheet};
+use crate::models::balance::format_delta;
+use crate::pages::calendars::date_utils::get_month_name;
+use leptos::prelude::*;
+
+const STYLE: &str = "
+    @page { size: A4; margin: 15mm; }
+    body { font-family: sans-serif; font-size: 11px; max-width: 800px; margin: 20px auto; color: #222; }
+    table { width: 100%; border-collapse: collapse; }
+    th, td { padding: 3px 6px; border-bottom: 1px solid #ccc; text-align: left; }
+    .number { text-align: right; }
+    .total td { font-weight: bold; border-top: 2px solid #222; border-bottom: none; }
+    .signatures { display: flex; justify-content: space-between; margin-top: 60px; }
+    .signatures div { width: 40%; border-top: 1px solid #222; padding-top: 4px; }
+    tr { -webkit-print-color-adjust: exact; print-color-adjust: exact; }
+    @media print { body { margin: 0; max-width: none; } }
+";
+
+/// The timesheet as a standalone HTML page laid out for printing
+pub fn html(timesheet: &Timesheet) -> String {
+    let rows = timesheet
+        .balance
+        .days
+        .iter()
+        .map(|day| {
+            let holiday = timesheet.work_calendar.holiday_on(day.date).is_some();
+            let background =
+                day_shade(day, holiday).map(|colour| format!("background-color: #{colour:06x}"));
+            view! {
+                <tr style=background>
+                    <td>{day.date.format("%Y-%m-%d").to_string()}</td>
+                    <td>{day.date.format("%A").to_string()}</td>
+                    <td>{day_type(day, &timesheet.work_calendar).to_string()}</td>
+                    <td class="number">{format!("{:.2}", day.logged_hours)}</td>
+                    <td class="number">{format!("{:.2}", day.expected_hours)}</td>
+                    <td class="number">{format_delta(day.delta())}</td>
+                    <td>{day_note(&timesheet.entries, day)}</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+    let page = view! {
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <title>{format!("Timesheet {}", period_label(timesheet))}</title>
+                <style>{STYLE}</style>
+            </head>
+            <body>
+                <h1>{format!("Timesheet {}", period_label(timesheet))}</h1>
+                <p>{format!("Employee: {}", timesheet.employee)}</p>
+                <table>
+                    <tr>
+                        <th>"Date"</th>
+                        <th>"Weekday"</th>
+                        <th>"Type"</th>
+                        <th class="number">"Logged hours"</th>
+                        <th class="number">"Expected hours"</th>
+                        <th class="number">"Delta"</th>
+                        <th>"Note"</th>
+                    </tr>
+                    {rows}
+                    <tr class="total">
+                        <td colspan="3">"Total"</td>
+                        <td class="number">{format!("{:.2}", timesheet.balance.logged_hours)}</td>
+                        <td class="number">{format!("{:.2}", timesheet.balance.expected_month)}</td>
+                        <td class="number">{format_delta(month_delta(timesheet))}</td>
+                        <td></td>
+                    </tr>
+                </table>
+                <div class="signatures">
+                    <div>"Employee signature and date"</div>
+                    <div>"Client signature and date"</div>
+                </div>
+            </body>
+        </html>
+    };
+    format!("<!DOCTYPE html>{}", page.to_html())
+}
+
+/// The timesheet as a one-page PDF
+pub fn pdf(timesheet: &Timesheet) -> Vec<u8> {
+    const LEFT: f32 = 50.0;
+    const RIGHT: f32 = PAGE_WIDTH - 50.0;
+    const COLUMNS: [f32; 6] = [LEFT, 115.0, 185.0, 365.0, 430.0, 495.0];
+    const ROW: f32 = 15.0;
+
+    let mut page = Page::default();
+    let mut y = PAGE_HEIGHT - 60.0;
+    page.text(
+        LEFT,
+        y,
+        18.0,
+        true,
+        &format!("Timesheet {}", period_label(timesheet)),
+    );
+    y -= 24.0;
+    page.text(
+        LEFT,
+        y,
+        10.0,
+        false,
+        &format!("Employee: {}", timesheet.employee),
+    );
+
+    y -= 30.0;
+    for (x, heading) in COLUMNS.into_iter().zip([
+        "Date",
+        "Weekday",
+        "Type",
+        "Logged hours",
+        "Expected hours",
+        "Delta",
+    ]) {
+        page.text(x, y, 9.0, true, heading);
+    }
+    y -= 6.0;
+    page.rule(LEFT, RIGHT, y);
+    y -= ROW;
+    for day in &timesheet.balance.days {
+        let cells = [
+            day.date.format("%Y-%m-%d").to_string(),
+            day.date.format("%A").to_string(),
+            day_type(day, &timesheet.work_calendar).to_string(),
+            format!("{:.2}", day.logged_hours),
+            format!("{:.2}", day.expected_hours),
+            format_delta(day.delta()),
+        ];
+        for (x, cell) in COLUMNS.into_iter().zip(&cells) {
+            page.text(x, y, 9.0, false, cell);
+        }
+        y -= ROW;
+    }
+    page.rule(LEFT, RIGHT, y + ROW - 5.0);
+    y -= 4.0;
+    let totals = [
+        (COLUMNS[0], "Total".to_string()),
+        (COLUMNS[3], format!("{:.2}", timesheet.balance.logged_hours)),
+        (
+            COLUMNS[4],
+            format!("{:.2}", timesheet.balance.expected_month),
+        ),
+        (COLUMNS[5], format_delta(month_delta(timesheet))),
+    ];
+    for (x, cell) in totals {
+        page.text(x, y, 10.0, true, &cell);
+    }
+
+    // Signature lines sit at the bottom of the page whatever the length of the month
+    let signature = 90.0;
+    page.rule(LEFT, LEFT + 200.0, signature);
+    page.rule(RIGHT - 200.0, RIGHT, signature);
+    page.text(
+        LEFT,
+        signature - 14.0,
+        9.0,
+        false,
+        "Employee signature and date",
+    );
+    page.text(
+        RIGHT - 200.0,
+        signature - 14.0,
+        9.0,
+        false,
+        "Client signature and date",
+    );
+
+    pdf::render(vec![page])
+}
+
+/// Month of the timesheet, e.g. `October 2026`
+fn period_label(timesheet: &Timesheet) -> String {
+    format!("{} {}", get_month_name(timesheet.month), timesheet.year)
+}
+
+/// Difference between the hours logged and expected over the whole month
+fn month_delta(timesheet: &Timesheet) -> f32 {
+    timesheet.balance.logged_hours - timesheet.balance.expected_month
+}
@@ -0,0 +1,82 @@
+use super::{day_note, day_shade, day_type, Timesheet};
+use crate::models::balance::DayBalance;
+use chrono::Datelike;
+use rust_xlsxwriter::{
+    Color, ExcelDateTime, Format, FormatBorder, Formula, Workbook, Worksheet, XlsxError,
+};
+
+const HEADERS: [&str; 7] = [
+    "Date",
+    "Weekday",
+    "Type",
+    "Logged hours",
+    "Expected hours",
+    "Delta",
+    "Note",
+];
+const WIDTHS: [f64; 7] = [12.0, 12.0, 22.0, 14.0, 16.0, 10.0, 40.0];
+
+/// The timesheet as a workbook with one row per day and a totals row summing them
+pub fn xlsx(timesheet: &Timesheet) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(format!("{:04}-{:02}", timesheet.year, timesheet.month))?;
+    let bold = Format::new().set_bold();
+    for (col, (header, width)) in HEADERS.iter().zip(WIDTHS).enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &bold)?;
+        sheet.set_column_width(col as u16, width)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    let days = &timesheet.balance.days;
+    for (index, day) in days.iter().enumerate() {
+        let row = index as u32 + 1;
+        let holiday = timesheet.work_calendar.holiday_on(day.date).is_some();
+        let format = match day_shade(day, holiday) {
+            Some(colour) => Format::new().set_background_color(Color::RGB(colour)),
+            None => Format::new(),
+        };
+        let date = ExcelDateTime::from_ymd(
+            day.date.year() as u16,
+            day.date.month() as u8,
+            day.date.day() as u8,
+        )?;
+        let hours = format.clone().set_num_format("0.00");
+        sheet.write_date_with_format(row, 0, date, &format.clone().set_num_format("yyyy-mm-dd"))?;
+        sheet.write_string_with_format(row, 1, day.date.format("%A").to_string(), &format)?;
+        sheet.write_string_with_format(row, 2, day_type(day, &timesheet.work_calendar), &format)?;
+        sheet.write_number_with_format(row, 3, day.logged_hours, &hours)?;
+        sheet.write_number_with_format(row, 4, day.expected_hours, &hours)?;
+        // Rows are 1-based in formulas, so the data of row `row` sits in row `row + 1`
+        let formula =
+            Formula::new(format!("=D{0}-E{0}", row + 1)).set_result(format!("{:.2}", day.delta()));
+        sheet.write_formula_with_format(row, 5, formula, &hours)?;
+        sheet.write_string_with_format(row, 6, day_note(&timesheet.entries, day), &format)?;
+    }
+
+    write_totals(sheet, days)?;
+    workbook.save_to_buffer()
+}
+
+/// Totals row below the days, summing each column of hours with a live formula
+fn write_totals(sheet: &mut Worksheet, days: &[DayBalance]) -> Result<(), XlsxError> {
+    let row = days.len() as u32 + 1;
+    let total = Format::new().set_bold().set_border_top(FormatBorder::Thin);
+    let hours = total.clone().set_num_format("0.00");
+    let logged = days.iter().map(|day| day.logged_hours).sum::<f32>();
+    let expected = days.iter().map(|day| day.expected_hours).sum::<f32>();
+    sheet.write_string_with_format(row, 0, "Total", &total)?;
+    for col in [1, 2, 6] {
+        sheet.write_blank(row, col, &total)?;
+    }
+    for (col, letter, sum) in [
+        (3, 'D', logged),
+        (4, 'E', expected),
+        (5, 'F', logged - expected),
+    ] {
+        let formula =
+            Formula::new(format!("=SUM({letter}2:{letter}{row})")).set_result(format!("{sum:.2}"));
+        sheet.write_formula_with_format(row, col, formula, &hours)?;
+    }
+    Ok(())
+}
@@ -1,6 +1,13 @@
-// pub mod components;
+pub mod components;
+pub mod api;
+#[cfg(feature = "ssr")]
+pub mod app_state;
+#[cfg(feature = "ssr")]
+pub mod documents;
 pub mod models;
 pub mod pages;
+#[cfg(feature = "ssr")]
+pub mod store;
 
 pub use pages::calendars::Calendar;
 pub mod app;
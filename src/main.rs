@@ -1,3 +1,4 @@
+#![recursion_limit = "256"]
 
 #[cfg(feature = "ssr")]
 #[tokio::main]
@@ -7,6 +8,9 @@ async fn main() {
     use leptos::prelude::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
     use hours_counter::app::*;
+    use hours_counter::app_state::{friday_cycle_from_env, user_header_from_env, AppState};
+    use hours_counter::documents;
+    use hours_counter::store::StoreConfig;
 
     let conf = get_configuration(None).unwrap();
     let addr = conf.leptos_options.site_addr;
@@ -14,13 +18,24 @@ async fn main() {
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
+    let store_config = StoreConfig::from_env().unwrap();
+    let store = store_config.open().unwrap();
+    log!("storing hours in {:?} ({})", store_config.kind, store_config.path.display());
+    let state = AppState {
+        leptos_options: leptos_options.clone(),
+        store,
+        friday_cycle: friday_cycle_from_env().unwrap(),
+        user_header: user_header_from_env().unwrap(),
+    };
+
     let app = Router::new()
-        .leptos_routes(&leptos_options, routes, {
+        .merge(documents::routes())
+        .leptos_routes(&state, routes, {
             let leptos_options = leptos_options.clone();
             move || shell(leptos_options.clone())
         })
-        .fallback(leptos_axum::file_and_error_handler(shell))
-        .with_state(leptos_options);
+        .fallback(leptos_axum::file_and_error_handler::<AppState, _>(shell))
+        .with_state(state);
 
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
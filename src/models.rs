@@ -1,2 +1,24 @@
-// pub mod calendar_state;
-// pub mod day_data;
+pub mod balance;
+pub mod billing;
+pub mod calendar_state;
+pub mod day_data;
+pub mod export;
+pub mod flexitime;
+pub mod friday_cycle;
+pub mod holidays;
+pub mod ics;
+pub mod import;
+pub mod labour_code;
+pub mod project;
+pub mod timer;
+pub mod user_settings;
+pub mod vacation;
+pub mod work_calendar;
+pub mod work_schedule;
+pub mod work_span;
+
+/// Date of a test case, known to exist
+#[cfg(test)]
+pub(crate) fn date(year: i32, month: u32, day: u32) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
@@ -0,0 +1,317 @@
+use super::calendar_state::CalendarDate;
+use super::day_data::{DayData, EntryKind};
+use super::work_calendar::WorkCalendar;
+use chrono::prelude::*;
+use std::collections::HashMap;
+
+/// Logged and expected hours of a single day
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayBalance {
+    pub date: NaiveDate,
+    /// Kind of the day's entry, `None` if nothing was entered
+    pub kind: Option<EntryKind>,
+    /// Hours logged, or credited for an absence
+    pub logged_hours: f32,
+    pub expected_hours: f32,
+}
+
+impl DayBalance {
+    /// Overtime when positive, undertime when negative
+    pub fn delta(&self) -> f32 {
+        self.logged_hours - self.expected_hours
+    }
+}
+
+/// Logged hours of a month compared with the expected hours
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonthBalance {
+    /// Every day of the month, in date order
+    pub days: Vec<DayBalance>,
+    /// Hours logged in the whole month
+    pub logged_hours: f32,
+    /// Hours expected up to and including today
+    pub expected_to_date: f32,
+    /// Hours expected in the whole month
+    pub expected_month: f32,
+    /// Last day counted towards `expected_to_date`, `None` if the month has not started yet
+    pub last_counted_day: Option<NaiveDate>,
+}
+
+impl MonthBalance {
+    /// Hours logged or credited up to and including today
+    pub fn logged_to_date(&self) -> f32 {
+        self.days_to_date().map(|day| day.logged_hours).sum()
+    }
+
+    /// Overtime so far when positive, undertime when negative; days after today are not counted
+    /// yet, so booking a future absence does not change it
+    pub fn difference(&self) -> f32 {
+        self.logged_to_date() - self.expected_to_date
+    }
+
+    /// Days up to and including today, whose deltas are final
+    pub fn days_to_date(&self) -> impl Iterator<Item = &DayBalance> {
+        self.days
+            .iter()
+            .filter(|day| self.last_counted_day.is_some_and(|last| day.date <= last))
+    }
+
+    /// Number of days of each kind other than work that were entered, in [`EntryKind::ALL`] order
+    pub fn kind_counts(&self) -> Vec<(EntryKind, usize)> {
+        EntryKind::ALL
+            .into_iter()
+            .filter(|kind| *kind != EntryKind::Work)
+            .map(|kind| {
+                let count = self
+                    .days
+                    .iter()
+                    .filter(|day| day.kind == Some(kind))
+                    .count();
+                (kind, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+/// Compare the hours logged in a month with what the work calendar expects, counting expected
+/// hours up to and including `today`
+pub fn month_balance(
+    year: i32,
+    month: u32,
+    hours_data: &HashMap<CalendarDate, DayData>,
+    work_calendar: &WorkCalendar,
+    today: NaiveDate,
+) -> MonthBalance {
+    let days = NaiveDate::from_ymd_opt(year, month, 1)
+        .into_iter()
+        .flat_map(|first_day| first_day.iter_days())
+        .take_while(|date| date.month() == month)
+        .map(|date| {
+            let data = hours_data.get(&(date.year(), date.month(), date.day()));
+            let scheduled_hours = work_calendar.expected_hours(date);
+            let expected_hours =
+                data.map_or(scheduled_hours, |data| data.expected_hours(scheduled_hours));
+            DayBalance {
+                date,
+                kind: data.map(DayData::kind),
+                logged_hours: data
+                    .map(|data| data.credited_hours(expected_hours))
+                    .unwrap_or(0.0),
+                expected_hours,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let last_counted_day = days
+        .iter()
+        .rev()
+        .map(|day| day.date)
+        .find(|date| *date <= today);
+    let expected_to_date = days
+        .iter()
+        .filter(|day| day.date <= today)
+        .map(|day| day.expected_hours)
+        .sum();
+
+    MonthBalance {
+        logged_hours: days.iter().map(|day| day.logged_hours).sum(),
+        expected_month: days.iter().map(|day| day.expected_hours).sum(),
+        expected_to_date,
+        last_counted_day,
+        days,
+    }
+}
+
+/// Format an hour difference with an explicit sign, e.g. `+1.5h` or `-0.3h`
+pub fn format_delta(hours: f32) -> String {
+    let hours = (hours * 10.0).round() / 10.0;
+    if hours > 0.0 {
+        format!("+{hours:.1}h")
+    } else if hours < 0.0 {
+        format!("{hours:.1}h")
+    } else {
+        "0.0h".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+    use crate::models::holidays::HolidayCalendar;
+
+    #[test]
+    fn empty_month_expects_every_scheduled_day() {
+        let balance = month_balance(
+            2025,
+            2,
+            &HashMap::new(),
+            &WorkCalendar::four_day_week(),
+            date(2025, 3, 1),
+        );
+
+        assert_eq!(balance.days.len(), 28);
+        assert_eq!(balance.logged_hours, 0.0);
+        assert_eq!(balance.expected_month, 16.0 * 8.0);
+        assert_eq!(balance.expected_to_date, balance.expected_month);
+        assert_eq!(balance.difference(), -128.0);
+    }
+
+    #[test]
+    fn expected_to_date_stops_at_today() {
+        // Monday 3 to Wednesday 5 February 2025, plus the first four-day week
+        let balance = month_balance(
+            2025,
+            2,
+            &HashMap::new(),
+            &WorkCalendar::four_day_week(),
+            date(2025, 2, 5),
+        );
+
+        assert_eq!(balance.expected_to_date, 3.0 * 8.0);
+        assert_eq!(balance.last_counted_day, Some(date(2025, 2, 5)));
+        assert_eq!(balance.days_to_date().count(), 5);
+    }
+
+    #[test]
+    fn future_month_has_nothing_expected_to_date() {
+        let balance = month_balance(
+            2025,
+            2,
+            &HashMap::new(),
+            &WorkCalendar::four_day_week(),
+            date(2025, 1, 31),
+        );
+
+        assert_eq!(balance.expected_to_date, 0.0);
+        assert_eq!(balance.last_counted_day, None);
+        assert_eq!(balance.days_to_date().count(), 0);
+    }
+
+    #[test]
+    fn logged_hours_give_per_day_deltas() {
+        let hours_data = HashMap::from([
+            ((2025, 2, 3), DayData::new(9, 30)),
+            ((2025, 2, 4), DayData::new(6, 0)),
+            ((2025, 2, 8), DayData::new(2, 0)),
+        ]);
+        let balance = month_balance(
+            2025,
+            2,
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            date(2025, 2, 8),
+        );
+
+        let delta_on = |day: u32| balance.days[day as usize - 1].delta();
+        assert_eq!(delta_on(3), 1.5);
+        assert_eq!(delta_on(4), -2.0);
+        assert_eq!(delta_on(7), 0.0);
+        assert_eq!(delta_on(8), 2.0);
+        assert_eq!(balance.logged_hours, 17.5);
+        assert_eq!(balance.difference(), 17.5 - 32.0);
+    }
+
+    #[test]
+    fn absences_are_credited_the_expected_hours() {
+        let hours_data = HashMap::from([
+            (
+                (2025, 2, 3),
+                DayData::default().with_kind(EntryKind::Vacation),
+            ),
+            (
+                (2025, 2, 4),
+                DayData::default().with_kind(EntryKind::SickLeave),
+            ),
+            (
+                (2025, 2, 5),
+                DayData::new(10, 0).with_kind(EntryKind::BusinessTrip),
+            ),
+            (
+                (2025, 2, 6),
+                DayData::new(4, 0).with_kind(EntryKind::BusinessTrip),
+            ),
+            (
+                (2025, 2, 8),
+                DayData::default().with_kind(EntryKind::Vacation),
+            ),
+        ]);
+        let balance = month_balance(
+            2025,
+            2,
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            date(2025, 2, 8),
+        );
+
+        assert_eq!(balance.logged_hours, 8.0 + 8.0 + 10.0 + 8.0);
+        assert_eq!(balance.difference(), 2.0);
+        assert_eq!(
+            balance.kind_counts(),
+            vec![
+                (EntryKind::Vacation, 2),
+                (EntryKind::SickLeave, 1),
+                (EntryKind::BusinessTrip, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn absences_after_today_are_not_credited_yet() {
+        let today = date(2025, 2, 5);
+        let mut hours_data = HashMap::from([((2025, 2, 3), DayData::new(8, 0))]);
+        let before = month_balance(2025, 2, &hours_data, &WorkCalendar::four_day_week(), today);
+
+        hours_data.insert(
+            (2025, 2, 10),
+            DayData::default().with_kind(EntryKind::Vacation),
+        );
+        let balance = month_balance(2025, 2, &hours_data, &WorkCalendar::four_day_week(), today);
+
+        assert_eq!(balance.difference(), before.difference());
+        assert_eq!(balance.logged_to_date(), 8.0);
+        assert_eq!(balance.logged_hours, 16.0);
+    }
+
+    #[test]
+    fn unpaid_leave_is_neither_credited_nor_expected() {
+        let hours_data = HashMap::from([(
+            (2025, 2, 3),
+            DayData::default().with_kind(EntryKind::UnpaidLeave),
+        )]);
+        let balance = month_balance(
+            2025,
+            2,
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            date(2025, 2, 4),
+        );
+
+        assert_eq!(balance.days[2].logged_hours, 0.0);
+        assert_eq!(balance.days[2].expected_hours, 0.0);
+        assert_eq!(balance.logged_hours, 0.0);
+        assert_eq!(balance.expected_to_date, 8.0);
+        assert_eq!(balance.expected_month, 15.0 * 8.0);
+    }
+
+    #[test]
+    fn holidays_are_not_expected() {
+        let work_calendar = WorkCalendar {
+            holidays: HolidayCalendar::poland(),
+            ..WorkCalendar::four_day_week()
+        };
+        // Easter Monday, 21 April 2025, is one of 18 Mondays to Thursdays
+        let balance = month_balance(2025, 4, &HashMap::new(), &work_calendar, date(2025, 4, 30));
+
+        assert_eq!(balance.days[20].expected_hours, 0.0);
+        assert_eq!(balance.expected_month, 17.0 * 8.0);
+    }
+
+    #[test]
+    fn deltas_are_formatted_with_a_sign() {
+        assert_eq!(format_delta(1.5), "+1.5h");
+        assert_eq!(format_delta(-0.25), "-0.3h");
+        assert_eq!(format_delta(0.01), "0.0h");
+    }
+}
@@ -0,0 +1,222 @@
+use super::{calendar_state::CalendarDate, day_data::DayData, project::Project};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Price of an hour of work on a project from a date on, in hundredths of the currency
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourlyRate {
+    pub from: NaiveDate,
+    pub cents: u64,
+}
+
+/// Details printed on every invoice
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BillingDetails {
+    /// Currency code printed after amounts, e.g. `EUR`
+    pub currency: String,
+    /// Name and address of the person invoicing, one line each
+    pub issuer: String,
+    /// Days from issue until an invoice is due
+    pub payment_days: u32,
+}
+
+impl Default for BillingDetails {
+    fn default() -> Self {
+        Self {
+            currency: "EUR".to_string(),
+            issuer: String::new(),
+            payment_days: 14,
+        }
+    }
+}
+
+/// Time billed at one rate
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub minutes: u32,
+    pub rate_cents: u64,
+    pub amount_cents: u64,
+}
+
+/// An issued invoice, kept as issued so that its documents never change
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Invoice {
+    /// Number within the year of issue, starting at 1
+    pub sequence: u32,
+    pub issued: NaiveDate,
+    pub due: NaiveDate,
+    /// Month billed, as (year, month)
+    pub period: (i32, u32),
+    pub project: u64,
+    pub client: String,
+    pub issuer: String,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+}
+
+impl Invoice {
+    /// Invoice number, e.g. `2026-007`
+    pub fn number(&self) -> String {
+        format!("{}-{:03}", self.issued.year(), self.sequence)
+    }
+
+    pub fn total_cents(&self) -> u64 {
+        self.lines.iter().map(|line| line.amount_cents).sum()
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.lines.iter().map(|line| line.minutes).sum()
+    }
+
+    /// Whether both invoices bill the same project for the same month
+    pub fn bills_same_time(&self, other: &Invoice) -> bool {
+        self.project == other.project && self.period == other.period
+    }
+}
+
+/// Time logged to a project, one line per rate in effect; time before the project's first
+/// rate is left out
+pub fn billable_lines(project: &Project, entries: &[(CalendarDate, DayData)]) -> Vec<InvoiceLine> {
+    let mut lines = Vec::<(NaiveDate, InvoiceLine)>::new();
+    for &((year, month, day), ref data) in entries {
+        let Some(rate) =
+            NaiveDate::from_ymd_opt(year, month, day).and_then(|date| project.rate_on(date))
+        else {
+            continue;
+        };
+        let minutes = data
+            .sessions()
+            .iter()
+            .filter(|session| session.project == Some(project.id))
+            .map(|session| session.total_minutes())
+            .sum::<u32>();
+        if minutes == 0 {
+            continue;
+        }
+        match lines.iter_mut().find(|(from, _)| *from == rate.from) {
+            Some((_, line)) => line.minutes += minutes,
+            None => lines.push((
+                rate.from,
+                InvoiceLine {
+                    description: project.name.clone(),
+                    minutes,
+                    rate_cents: rate.cents,
+                    amount_cents: 0,
+                },
+            )),
+        }
+    }
+
+    let rate_count = lines.len();
+    lines
+        .into_iter()
+        .map(|(from, mut line)| {
+            if rate_count > 1 {
+                line.description = format!("{} (rate from {from})", line.description);
+            }
+            line.amount_cents = amount_cents(line.minutes, line.rate_cents);
+            line
+        })
+        .collect()
+}
+
+/// Price of some minutes at an hourly rate, rounded to the nearest cent
+pub fn amount_cents(minutes: u32, rate_cents: u64) -> u64 {
+    (u64::from(minutes) * rate_cents + 30) / 60
+}
+
+/// Amount with two decimals and the currency, e.g. `1234.50 EUR`
+pub fn format_amount(cents: u64, currency: &str) -> String {
+    format!("{}.{:02} {currency}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+    use crate::models::day_data::Session;
+
+    fn project() -> Project {
+        Project {
+            id: 1,
+            name: "Website".to_string(),
+            colour: "#3a7bd5".to_string(),
+            active: true,
+            client: String::new(),
+            rates: vec![
+                HourlyRate {
+                    from: date(2025, 3, 10),
+                    cents: 5000,
+                },
+                HourlyRate {
+                    from: date(2025, 3, 17),
+                    cents: 6000,
+                },
+            ],
+        }
+    }
+
+    fn logged(minutes: u32, project: Option<u64>) -> DayData {
+        DayData::from_sessions(vec![
+            Session::new(minutes / 60, minutes % 60).with_project(project)
+        ])
+    }
+
+    #[test]
+    fn the_rate_in_effect_is_the_latest_one_started() {
+        let project = project();
+        assert_eq!(project.rate_on(date(2025, 3, 9)), None);
+        assert_eq!(project.rate_on(date(2025, 3, 10)).unwrap().cents, 5000);
+        assert_eq!(project.rate_on(date(2025, 3, 16)).unwrap().cents, 5000);
+        assert_eq!(project.rate_on(date(2025, 3, 17)).unwrap().cents, 6000);
+        assert_eq!(project.rate_on(date(2026, 1, 1)).unwrap().cents, 6000);
+    }
+
+    #[test]
+    fn a_month_is_split_into_one_line_per_rate() {
+        let entries = [
+            ((2025, 3, 7), logged(120, Some(1))),
+            ((2025, 3, 11), logged(100, Some(1))),
+            ((2025, 3, 12), logged(60, Some(2))),
+            ((2025, 3, 14), logged(7, Some(1))),
+            ((2025, 3, 18), logged(61, Some(1))),
+            ((2025, 3, 19), logged(0, Some(1))),
+        ];
+        assert_eq!(
+            billable_lines(&project(), &entries),
+            vec![
+                InvoiceLine {
+                    description: "Website (rate from 2025-03-10)".to_string(),
+                    minutes: 107,
+                    rate_cents: 5000,
+                    amount_cents: 8917,
+                },
+                InvoiceLine {
+                    description: "Website (rate from 2025-03-17)".to_string(),
+                    minutes: 61,
+                    rate_cents: 6000,
+                    amount_cents: 6100,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_single_rate_keeps_the_project_name() {
+        let lines = billable_lines(&project(), &[((2025, 3, 11), logged(90, Some(1)))]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].description, "Website");
+        assert_eq!(lines[0].amount_cents, 7500);
+    }
+
+    #[test]
+    fn amounts_are_rounded_to_the_nearest_cent() {
+        assert_eq!(amount_cents(1, 5000), 83);
+        assert_eq!(amount_cents(1, 6030), 101);
+        assert_eq!(amount_cents(1, 6029), 100);
+        assert_eq!(amount_cents(0, 5000), 0);
+        assert_eq!(format_amount(8917, "EUR"), "89.17 EUR");
+        assert_eq!(format_amount(5, "PLN"), "0.05 PLN");
+    }
+}
@@ -1,15 +1,20 @@
 use super::day_data::DayData;
-use chrono::prelude::*;
-use leptos::*;
-use std::collections::{HashMap, HashSet};
+use super::friday_cycle::WorkingFridays;
+use chrono::NaiveDate;
+use leptos::prelude::*;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 /// Represents a calendar date as (year, month, day)
 pub type CalendarDate = (i32, u32, u32);
 
+/// Years the calendar can be opened on, by the routes and the month picker alike
+pub const CALENDAR_YEARS: RangeInclusive<i32> = 1..=9999;
+
 #[derive(Clone)]
 pub struct CalendarState {
     hours_data: RwSignal<HashMap<CalendarDate, DayData>>,
-    working_fridays: RwSignal<HashSet<CalendarDate>>,
+    working_fridays: RwSignal<WorkingFridays>,
     selected_date: RwSignal<Option<CalendarDate>>,
     show_modal: RwSignal<bool>,
 }
@@ -18,10 +23,10 @@ impl CalendarState {
     /// Initialize calendar state
     pub fn new() -> Self {
         Self {
-            hours_data: create_rw_signal(HashMap::new()),
-            working_fridays: create_rw_signal(HashSet::new()),
-            selected_date: create_rw_signal(None),
-            show_modal: create_rw_signal(false),
+            hours_data: RwSignal::new(HashMap::new()),
+            working_fridays: RwSignal::new(WorkingFridays::default()),
+            selected_date: RwSignal::new(None),
+            show_modal: RwSignal::new(false),
         }
     }
 
@@ -33,13 +38,18 @@ impl CalendarState {
         self.show_modal.set(false);
     }
 
-    /// Toggle working Friday status
+    /// Toggle working Friday status, overriding the Friday cycle for that date
     pub fn toggle_working_friday(&self, date: CalendarDate) {
+        let (year, month, day) = date;
+        let Some(naive_date) = NaiveDate::from_ymd_opt(year, month, day) else {
+            return;
+        };
         self.working_fridays.update(|fridays| {
-            if fridays.contains(&date) {
-                fridays.remove(&date);
+            let working = !fridays.is_working_friday(naive_date);
+            if working == fridays.cycle.is_working_friday(naive_date) {
+                fridays.overrides.remove(&date);
             } else {
-                fridays.insert(date);
+                fridays.overrides.insert(date, working);
             }
         });
     }
@@ -48,7 +58,7 @@ impl CalendarState {
         self.hours_data
     }
 
-    pub fn get_working_fridays(&self) -> RwSignal<HashSet<CalendarDate>> {
+    pub fn get_working_fridays(&self) -> RwSignal<WorkingFridays> {
         self.working_fridays
     }
 
@@ -61,3 +71,9 @@ impl CalendarState {
     }
 }
 
+impl Default for CalendarState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
@@ -1,9 +1,199 @@
+use super::work_span::WorkSpan;
 use serde::{Deserialize, Serialize};
 
+/// What a day's entry records
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EntryKind {
+    /// Time actually worked
+    #[default]
+    Work,
+    Vacation,
+    /// Half a day of vacation, with the other half logged as work
+    HalfDayVacation,
+    SickLeave,
+    /// Day off in lieu of a public holiday
+    PublicHoliday,
+    /// Leave that expects no time on the day
+    UnpaidLeave,
+    /// Work away from the office, credited at least the expected hours
+    BusinessTrip,
+}
+
+impl EntryKind {
+    pub const ALL: [EntryKind; 7] = [
+        EntryKind::Work,
+        EntryKind::Vacation,
+        EntryKind::HalfDayVacation,
+        EntryKind::SickLeave,
+        EntryKind::PublicHoliday,
+        EntryKind::UnpaidLeave,
+        EntryKind::BusinessTrip,
+    ];
+
+    /// Label shown in the calendar
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryKind::Work => "Work",
+            EntryKind::Vacation => "Vacation",
+            EntryKind::HalfDayVacation => "Half-day vacation",
+            EntryKind::SickLeave => "Sick leave",
+            EntryKind::PublicHoliday => "Public holiday",
+            EntryKind::UnpaidLeave => "Unpaid leave",
+            EntryKind::BusinessTrip => "Business trip",
+        }
+    }
+
+    /// Whether the entry marks an absence rather than logged time
+    pub fn is_absence(&self) -> bool {
+        !matches!(
+            self,
+            EntryKind::Work | EntryKind::HalfDayVacation | EntryKind::BusinessTrip
+        )
+    }
+}
+
+/// A stretch of work within a day, e.g. the morning on site
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub hours: u32,
+    pub minutes: u32,
+    /// Clock-in and clock-out times the duration was derived from, if they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<WorkSpan>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    /// Id of the project the time is billed to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Session {
+    /// Create a session lasting a duration
+    pub fn new(hours: u32, minutes: u32) -> Self {
+        Self {
+            hours,
+            minutes,
+            ..Self::default()
+        }
+    }
+
+    /// Create a session worth the time worked within a span
+    pub fn from_span(span: WorkSpan) -> Self {
+        let worked_minutes = span.worked_minutes();
+        Self {
+            span: Some(span),
+            ..Self::new(worked_minutes / 60, worked_minutes % 60)
+        }
+    }
+
+    /// Same session with a note
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = note.into();
+        self
+    }
+
+    /// Same session billed to a project
+    pub fn with_project(mut self, project: Option<u64>) -> Self {
+        self.project = project;
+        self
+    }
+
+    /// Same session with tags, trimmed and without empty or repeated ones
+    pub fn with_tags<T: AsRef<str>>(mut self, tags: impl IntoIterator<Item = T>) -> Self {
+        self.tags.clear();
+        for tag in tags {
+            let tag = tag.as_ref().trim();
+            if !tag.is_empty() && !self.tags.iter().any(|other| other == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+        self
+    }
+
+    /// Minutes worked in the session
+    pub fn total_minutes(&self) -> u32 {
+        self.hours.saturating_mul(60).saturating_add(self.minutes)
+    }
+
+    /// Whether the session records nothing: no time, span, note, project or tags
+    pub fn is_empty(&self) -> bool {
+        self.total_minutes() == 0
+            && self.span.is_none()
+            && self.note.is_empty()
+            && self.project.is_none()
+            && self.tags.is_empty()
+    }
+
+    /// Extend the session by one on the same project starting when it ends, returning whether
+    /// it did
+    pub fn extend_with(&mut self, next: &Session) -> bool {
+        let (Some(span), Some(next_span)) = (&self.span, &next.span) else {
+            return false;
+        };
+        if span.end != next_span.start
+            || span.span_minutes() + next_span.span_minutes() > 24 * 60
+            || self.project != next.project
+        {
+            return false;
+        }
+        let mut breaks = span.breaks.clone();
+        breaks.extend(next_span.breaks.iter().cloned());
+        let extended = WorkSpan {
+            start: span.start,
+            end: next_span.end,
+            breaks,
+        };
+        *self = Session {
+            note: std::mem::take(&mut self.note),
+            project: self.project,
+            tags: std::mem::take(&mut self.tags),
+            ..Session::from_span(extended)
+        };
+        true
+    }
+}
+
+/// Fields of a stored day, including the single span of entries saved before sessions existed
+#[derive(Deserialize)]
+struct DayDataFields {
+    #[serde(default)]
+    hours: u32,
+    #[serde(default)]
+    minutes: u32,
+    #[serde(default)]
+    kind: EntryKind,
+    #[serde(default)]
+    sessions: Vec<Session>,
+    #[serde(default)]
+    span: Option<WorkSpan>,
+}
+
+impl From<DayDataFields> for DayData {
+    fn from(fields: DayDataFields) -> Self {
+        let sessions = if !fields.sessions.is_empty() {
+            fields.sessions
+        } else if let Some(span) = fields.span {
+            vec![Session::from_span(span)]
+        } else if fields.hours > 0 || fields.minutes > 0 {
+            vec![Session::new(fields.hours, fields.minutes)]
+        } else {
+            Vec::new()
+        };
+        Self::from_sessions(sessions).with_kind(fields.kind)
+    }
+}
+
+/// Time logged on a day; `hours` and `minutes` always hold the total of the sessions
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "DayDataFields")]
 pub struct DayData {
     hours: u32,
     minutes: u32,
+    kind: EntryKind,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sessions: Vec<Session>,
 }
 
 impl DayData {
@@ -12,12 +202,28 @@ impl DayData {
         self.hours as f32 + (self.minutes as f32 / 60.0)
     }
 
-    /// Create DayData from decimal hours
+    /// Create DayData from decimal hours, rounded to the nearest minute
     pub fn from_hours(hours: f32) -> Self {
-        let total_minutes = (hours * 60.0) as u32;
-        Self {
-            hours: total_minutes / 60,
-            minutes: total_minutes % 60,
+        let total_minutes = (hours * 60.0).round() as u32;
+        Self::new(total_minutes / 60, total_minutes % 60)
+    }
+
+    /// Hours counted towards the balance: absences are credited the hours expected on the day
+    pub fn credited_hours(&self, expected_hours: f32) -> f32 {
+        match self.kind {
+            EntryKind::Work => self.to_hours(),
+            EntryKind::HalfDayVacation => self.to_hours() + expected_hours / 2.0,
+            EntryKind::BusinessTrip => self.to_hours().max(expected_hours),
+            EntryKind::UnpaidLeave => 0.0,
+            _ => expected_hours,
+        }
+    }
+
+    /// Hours expected on the day given what the schedule asks for: none on unpaid leave
+    pub fn expected_hours(&self, scheduled_hours: f32) -> f32 {
+        match self.kind {
+            EntryKind::UnpaidLeave => 0.0,
+            _ => scheduled_hours,
         }
     }
 
@@ -26,9 +232,86 @@ impl DayData {
         format!("{}:{:02}", self.hours, self.minutes)
     }
 
+    /// Whole hours logged
+    pub fn hours(&self) -> u32 {
+        self.hours
+    }
+
+    /// Minutes logged on top of the whole hours
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    /// What the entry records
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Sessions worked during the day, in the order they were entered
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
     /// Create new DayData instance
     pub fn new(hours: u32, minutes: u32) -> Self {
-        Self { hours, minutes }
+        Self::from_sessions(vec![Session::new(hours, minutes)])
+    }
+
+    /// Create DayData worth the time worked within a span
+    pub fn from_span(span: WorkSpan) -> Self {
+        Self::from_sessions(vec![Session::from_span(span)])
+    }
+
+    /// Create DayData totalling the sessions of a day, leaving out the empty ones
+    pub fn from_sessions(mut sessions: Vec<Session>) -> Self {
+        sessions.retain(|session| !session.is_empty());
+        let total_minutes = sessions.iter().fold(0u32, |total, session| {
+            total.saturating_add(session.total_minutes())
+        });
+        Self {
+            hours: total_minutes / 60,
+            minutes: total_minutes % 60,
+            kind: EntryKind::Work,
+            sessions,
+        }
+    }
+
+    /// Same entry recording another kind of day
+    pub fn with_kind(mut self, kind: EntryKind) -> Self {
+        self.kind = kind;
+        self
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_are_totalled() {
+        let day = DayData::from_sessions(vec![Session::new(3, 45), Session::new(4, 30)]);
+
+        assert_eq!((day.hours(), day.minutes()), (8, 15));
+        assert_eq!(day.sessions().len(), 2);
+    }
+
+    #[test]
+    fn empty_sessions_are_left_out() {
+        let day = DayData::from_sessions(vec![
+            Session::new(0, 0),
+            Session::new(2, 0),
+            Session::new(0, 0).with_note("Called the client"),
+        ]);
+
+        assert_eq!(day.sessions().len(), 2);
+        assert_eq!(day.sessions()[1].note, "Called the client");
+        assert!(DayData::new(0, 0).sessions().is_empty());
+    }
+
+    #[test]
+    fn huge_durations_saturate_instead_of_overflowing() {
+        let day = DayData::from_sessions(vec![Session::new(u32::MAX, 59), Session::new(1, 0)]);
+
+        assert!(day.to_hours() > 24.0);
+    }
+}
@@ -0,0 +1,156 @@
+use super::{
+    calendar_state::CalendarDate,
+    day_data::{DayData, EntryKind},
+    work_calendar::WorkCalendar,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Character separating the fields of an exported CSV file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Semicolon,
+    Tab,
+}
+
+impl Delimiter {
+    pub const ALL: [Delimiter; 3] = [Delimiter::Comma, Delimiter::Semicolon, Delimiter::Tab];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "Comma (,)",
+            Delimiter::Semicolon => "Semicolon (;)",
+            Delimiter::Tab => "Tab",
+        }
+    }
+
+    /// Name used in export URLs
+    pub fn key(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "comma",
+            Delimiter::Semicolon => "semicolon",
+            Delimiter::Tab => "tab",
+        }
+    }
+
+    pub fn byte(self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Semicolon => b';',
+            Delimiter::Tab => b'\t',
+        }
+    }
+}
+
+/// Character separating the whole and the fractional part of exported numbers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecimalSeparator {
+    #[default]
+    Point,
+    Comma,
+}
+
+impl DecimalSeparator {
+    pub const ALL: [DecimalSeparator; 2] = [DecimalSeparator::Point, DecimalSeparator::Comma];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DecimalSeparator::Point => "Point (1.5)",
+            DecimalSeparator::Comma => "Comma (1,5)",
+        }
+    }
+
+    /// Name used in export URLs
+    pub fn key(self) -> &'static str {
+        match self {
+            DecimalSeparator::Point => "point",
+            DecimalSeparator::Comma => "comma",
+        }
+    }
+
+    /// A number with two decimals, e.g. `7.50` or `7,50`
+    pub fn format(self, value: f32) -> String {
+        let formatted = format!("{value:.2}");
+        match self {
+            DecimalSeparator::Point => formatted,
+            DecimalSeparator::Comma => formatted.replace('.', ","),
+        }
+    }
+
+    /// Read a number written with this separator
+    pub fn parse(self, value: &str) -> Option<f32> {
+        match self {
+            DecimalSeparator::Point => value.trim().parse().ok(),
+            DecimalSeparator::Comma => value.trim().replace(',', ".").parse().ok(),
+        }
+    }
+}
+
+/// Date range to export and how to write a CSV file of it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub delimiter: Delimiter,
+    #[serde(default)]
+    pub decimal: DecimalSeparator,
+}
+
+impl ExportOptions {
+    /// The options as an URL query string
+    pub fn query(&self) -> String {
+        format!(
+            "from={}&to={}&delimiter={}&decimal={}",
+            self.from,
+            self.to,
+            self.delimiter.key(),
+            self.decimal.key(),
+        )
+    }
+}
+
+/// One logged day as exported
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportRow {
+    pub date: NaiveDate,
+    pub minutes: u32,
+    /// Logged hours as a decimal number, see [`DayData::to_hours`]
+    pub hours: f32,
+    pub kind: EntryKind,
+    /// Notes of the day's sessions
+    pub note: String,
+    pub expected_hours: f32,
+}
+
+/// Export rows of logged days, in date order
+pub fn export_rows(
+    entries: &[(CalendarDate, DayData)],
+    work_calendar: &WorkCalendar,
+) -> Vec<ExportRow> {
+    entries
+        .iter()
+        .filter_map(|&((year, month, day), ref data)| {
+            let date = NaiveDate::from_ymd_opt(year, month, day)?;
+            let note = data
+                .sessions()
+                .iter()
+                .map(|session| session.note.as_str())
+                .filter(|note| !note.is_empty())
+                .collect::<Vec<_>>()
+                .join("; ");
+            Some(ExportRow {
+                date,
+                minutes: data.hours() * 60 + data.minutes(),
+                hours: data.to_hours(),
+                kind: data.kind(),
+                note,
+                expected_hours: data.expected_hours(work_calendar.expected_hours(date)),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,200 @@
+use super::calendar_state::CalendarDate;
+use super::day_data::DayData;
+use super::work_calendar::WorkCalendar;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where the flexitime account starts
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlexitimeAccount {
+    /// First day counted, `None` to start on the first logged day
+    pub start: Option<NaiveDate>,
+    /// Balance brought in on the first day, negative for a deficit
+    pub opening_minutes: i32,
+}
+
+/// Manual change of the flexitime balance, e.g. overtime paid out
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceAdjustment {
+    pub id: u64,
+    pub date: CalendarDate,
+    /// Change of the balance, negative when hours are taken off the account
+    pub minutes: i32,
+    pub note: String,
+}
+
+/// Flexitime balance around a month
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlexitimeBalance {
+    /// Hours carried over from the months before
+    pub carried_in: f32,
+    /// Hours at the end of the month
+    pub closing: f32,
+}
+
+/// Hours on the flexitime account at the end of `until`: the opening balance, plus the hours
+/// logged or credited and minus the hours expected from `start` up to `today`, plus the adjustments
+pub fn flexitime_balance(
+    start: NaiveDate,
+    opening_minutes: i32,
+    until: NaiveDate,
+    hours_data: &HashMap<CalendarDate, DayData>,
+    work_calendar: &WorkCalendar,
+    adjustments: &[BalanceAdjustment],
+    today: NaiveDate,
+) -> f32 {
+    let mut balance = opening_minutes as f32 / 60.0;
+    if until < start {
+        return balance;
+    }
+
+    for date in start.iter_days().take_while(|date| *date <= until) {
+        if date > today {
+            break;
+        }
+        let mut expected_hours = work_calendar.expected_hours(date);
+        if let Some(data) = hours_data.get(&(date.year(), date.month(), date.day())) {
+            expected_hours = data.expected_hours(expected_hours);
+            balance += data.credited_hours(expected_hours);
+        }
+        balance -= expected_hours;
+    }
+
+    let from = (start.year(), start.month(), start.day());
+    let to = (until.year(), until.month(), until.day());
+    balance
+        + adjustments
+            .iter()
+            .filter(|adjustment| (from..=to).contains(&adjustment.date))
+            .map(|adjustment| adjustment.minutes as f32 / 60.0)
+            .sum::<f32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+    use crate::models::day_data::EntryKind;
+
+    fn adjustment(id: u64, date: CalendarDate, minutes: i32) -> BalanceAdjustment {
+        BalanceAdjustment {
+            id,
+            date,
+            minutes,
+            note: String::new(),
+        }
+    }
+
+    #[test]
+    fn balance_carries_over_into_the_next_month() {
+        // Monday 27 to Thursday 30 January 2025, each an hour over, then Monday 3 February
+        let hours_data = HashMap::from([
+            ((2025, 1, 27), DayData::new(9, 0)),
+            ((2025, 1, 28), DayData::new(9, 0)),
+            ((2025, 1, 29), DayData::new(9, 0)),
+            ((2025, 1, 30), DayData::new(9, 0)),
+            ((2025, 2, 3), DayData::new(6, 30)),
+        ]);
+        let balance = |until| {
+            flexitime_balance(
+                date(2025, 1, 27),
+                90,
+                until,
+                &hours_data,
+                &WorkCalendar::four_day_week(),
+                &[],
+                date(2025, 2, 3),
+            )
+        };
+
+        assert_eq!(balance(date(2025, 1, 31)), 1.5 + 4.0);
+        assert_eq!(balance(date(2025, 2, 28)), 1.5 + 4.0 - 1.5);
+    }
+
+    #[test]
+    fn days_before_the_start_are_not_counted() {
+        let hours_data = HashMap::from([((2025, 2, 3), DayData::new(12, 0))]);
+        let balance = flexitime_balance(
+            date(2025, 2, 4),
+            0,
+            date(2025, 2, 4),
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            &[adjustment(1, (2025, 2, 3), 60)],
+            date(2025, 2, 4),
+        );
+
+        assert_eq!(balance, -8.0);
+        assert_eq!(
+            flexitime_balance(
+                date(2025, 2, 4),
+                -30,
+                date(2025, 2, 3),
+                &hours_data,
+                &WorkCalendar::four_day_week(),
+                &[],
+                date(2025, 2, 4),
+            ),
+            -0.5
+        );
+    }
+
+    #[test]
+    fn days_after_today_are_not_expected_yet() {
+        let hours_data = HashMap::from([((2025, 2, 3), DayData::new(8, 0))]);
+        let balance = flexitime_balance(
+            date(2025, 2, 3),
+            0,
+            date(2025, 2, 28),
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            &[],
+            date(2025, 2, 3),
+        );
+
+        assert_eq!(balance, 0.0);
+    }
+
+    #[test]
+    fn absences_after_today_are_not_credited_yet() {
+        let hours_data = HashMap::from([
+            ((2025, 2, 3), DayData::new(8, 0)),
+            (
+                (2025, 2, 10),
+                DayData::default().with_kind(EntryKind::Vacation),
+            ),
+        ]);
+        let balance = flexitime_balance(
+            date(2025, 2, 3),
+            0,
+            date(2025, 2, 28),
+            &hours_data,
+            &WorkCalendar::four_day_week(),
+            &[],
+            date(2025, 2, 3),
+        );
+
+        assert_eq!(balance, 0.0);
+    }
+
+    #[test]
+    fn adjustments_up_to_the_end_are_added() {
+        let adjustments = [
+            adjustment(1, (2025, 2, 3), -120),
+            adjustment(2, (2025, 2, 7), 30),
+            adjustment(3, (2025, 3, 1), 600),
+        ];
+        let balance = flexitime_balance(
+            date(2025, 2, 3),
+            0,
+            date(2025, 2, 28),
+            &HashMap::new(),
+            &WorkCalendar::four_day_week(),
+            &adjustments,
+            date(2025, 2, 2),
+        );
+
+        assert_eq!(balance, -1.5);
+    }
+}
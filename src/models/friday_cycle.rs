@@ -0,0 +1,82 @@
+use super::calendar_state::CalendarDate;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Alternating pattern of working Fridays, counted from a known working Friday
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FridayCycle {
+    anchor: NaiveDate,
+    interval_weeks: u32,
+}
+
+impl FridayCycle {
+    /// Create a cycle where every `interval_weeks`-th Friday counted from `anchor` is worked.
+    /// An interval of 0 means no Friday is worked. Returns `None` if `anchor` is not a Friday.
+    pub fn new(anchor: NaiveDate, interval_weeks: u32) -> Option<Self> {
+        (anchor.weekday() == Weekday::Fri).then_some(Self {
+            anchor,
+            interval_weeks,
+        })
+    }
+
+    /// Working Friday the cycle is counted from
+    pub fn anchor(&self) -> NaiveDate {
+        self.anchor
+    }
+
+    /// Number of weeks between two working Fridays
+    pub fn interval_weeks(&self) -> u32 {
+        self.interval_weeks
+    }
+
+    /// Check whether a date is a Friday worked according to the cycle
+    pub fn is_working_friday(&self, date: NaiveDate) -> bool {
+        if date.weekday() != Weekday::Fri || self.interval_weeks == 0 {
+            return false;
+        }
+        let weeks = (date - self.anchor).num_weeks();
+        weeks.rem_euclid(self.interval_weeks as i64) == 0
+    }
+}
+
+impl Default for FridayCycle {
+    /// Every second Friday, starting 2025-01-03
+    fn default() -> Self {
+        Self {
+            anchor: NaiveDate::from_ymd_opt(2025, 1, 3).unwrap(),
+            interval_weeks: 2,
+        }
+    }
+}
+
+/// Friday cycle together with the Fridays whose working status was overridden by hand
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkingFridays {
+    pub cycle: FridayCycle,
+    pub overrides: HashMap<CalendarDate, bool>,
+}
+
+impl WorkingFridays {
+    /// Create from the cycle and the overridden Fridays
+    pub fn new(
+        cycle: FridayCycle,
+        overrides: impl IntoIterator<Item = (CalendarDate, bool)>,
+    ) -> Self {
+        Self {
+            cycle,
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+
+    /// Check whether a date is a worked Friday, preferring an override over the cycle
+    pub fn is_working_friday(&self, date: NaiveDate) -> bool {
+        if date.weekday() != Weekday::Fri {
+            return false;
+        }
+        self.overrides
+            .get(&(date.year(), date.month(), date.day()))
+            .copied()
+            .unwrap_or_else(|| self.cycle.is_working_friday(date))
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the date of a holiday is found in a given year
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HolidayRule {
+    /// Same day every year
+    Fixed { month: u32, day: u32 },
+    /// Number of days after Easter Sunday, negative for days before it
+    Easter { offset_days: i64 },
+    /// A single date, e.g. a company day off
+    Date { date: NaiveDate },
+}
+
+/// A named holiday, observed from `since` and up to `until` when set
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Holiday {
+    pub name: String,
+    pub rule: HolidayRule,
+    pub since: Option<i32>,
+    #[serde(default)]
+    pub until: Option<i32>,
+}
+
+impl Holiday {
+    /// Holiday on the same day every year
+    pub fn fixed(name: &str, month: u32, day: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            rule: HolidayRule::Fixed { month, day },
+            since: None,
+            until: None,
+        }
+    }
+
+    /// Holiday a number of days after Easter Sunday
+    pub fn easter(name: &str, offset_days: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            rule: HolidayRule::Easter { offset_days },
+            since: None,
+            until: None,
+        }
+    }
+
+    /// Holiday on a single date
+    pub fn on(name: &str, date: NaiveDate) -> Self {
+        Self {
+            name: name.to_string(),
+            rule: HolidayRule::Date { date },
+            since: None,
+            until: None,
+        }
+    }
+
+    /// Observe the holiday only from `year` onwards
+    pub fn since(mut self, year: i32) -> Self {
+        self.since = Some(year);
+        self
+    }
+
+    /// Observe the holiday only up to `year`
+    pub fn until(mut self, year: i32) -> Self {
+        self.until = Some(year);
+        self
+    }
+
+    /// Date of the holiday in a year, `None` if it is not observed that year
+    pub fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        if self.since.is_some_and(|since| year < since)
+            || self.until.is_some_and(|until| year > until)
+        {
+            return None;
+        }
+        match self.rule {
+            HolidayRule::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayRule::Easter { offset_days } => {
+                easter_sunday(year).checked_add_signed(chrono::Duration::days(offset_days))
+            }
+            HolidayRule::Date { date } => Some(date).filter(|date| date.year() == year),
+        }
+    }
+}
+
+/// Set of holidays on which no work is expected
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HolidayCalendar {
+    pub holidays: Vec<Holiday>,
+}
+
+impl HolidayCalendar {
+    /// Statutory public holidays in Poland
+    pub fn poland() -> Self {
+        Self {
+            holidays: vec![
+                Holiday::fixed("New Year's Day", 1, 1),
+                Holiday::fixed("Epiphany", 1, 6).since(2011),
+                Holiday::easter("Easter Sunday", 0),
+                Holiday::easter("Easter Monday", 1),
+                Holiday::fixed("Labour Day", 5, 1),
+                Holiday::fixed("Constitution Day", 5, 3),
+                Holiday::easter("Pentecost", 49),
+                Holiday::easter("Corpus Christi", 60),
+                Holiday::fixed("Assumption Day", 8, 15),
+                Holiday::fixed("All Saints' Day", 11, 1),
+                Holiday::fixed("Independence Day", 11, 11),
+                Holiday::fixed("Christmas Eve", 12, 24).since(2025),
+                Holiday::fixed("Christmas Day", 12, 25),
+                Holiday::fixed("Second Day of Christmas", 12, 26),
+            ],
+        }
+    }
+
+    /// Name of the holiday falling on a date
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&str> {
+        self.holidays
+            .iter()
+            .find(|holiday| holiday.date_in(date.year()) == Some(date))
+            .map(|holiday| holiday.name.as_str())
+    }
+
+    /// All holidays of a year, in date order
+    pub fn holidays_in(&self, year: i32) -> Vec<(NaiveDate, &str)> {
+        let mut holidays = self
+            .holidays
+            .iter()
+            .filter_map(|holiday| Some((holiday.date_in(year)?, holiday.name.as_str())))
+            .collect::<Vec<_>>();
+        holidays.sort();
+        holidays
+    }
+}
+
+/// Built-in holiday sets to choose from in the settings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HolidayPreset {
+    /// Also the preset of settings saved before holidays were counted, so their hours stay the same
+    #[default]
+    None,
+    Poland,
+}
+
+impl HolidayPreset {
+    pub const ALL: [HolidayPreset; 2] = [HolidayPreset::None, HolidayPreset::Poland];
+
+    /// Label shown in the settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            HolidayPreset::None => "No public holidays",
+            HolidayPreset::Poland => "Poland",
+        }
+    }
+
+    /// Holidays of the preset
+    pub fn calendar(&self) -> HolidayCalendar {
+        match self {
+            HolidayPreset::None => HolidayCalendar::default(),
+            HolidayPreset::Poland => HolidayCalendar::poland(),
+        }
+    }
+}
+
+/// Date of Easter Sunday in the Gregorian calendar (anonymous Gregorian computus)
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+
+    #[test]
+    fn easter_sunday_matches_known_dates() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2038), date(2038, 4, 25));
+        assert_eq!(easter_sunday(2285), date(2285, 3, 22));
+    }
+
+    #[test]
+    fn polish_holidays_follow_easter() {
+        let holidays = HolidayCalendar::poland();
+
+        assert_eq!(
+            holidays.holiday_on(date(2025, 4, 21)),
+            Some("Easter Monday")
+        );
+        assert_eq!(holidays.holiday_on(date(2025, 6, 8)), Some("Pentecost"));
+        assert_eq!(
+            holidays.holiday_on(date(2025, 6, 19)),
+            Some("Corpus Christi")
+        );
+        assert_eq!(holidays.holiday_on(date(2025, 6, 20)), None);
+    }
+
+    #[test]
+    fn polish_holidays_are_observed_from_their_first_year() {
+        let holidays = HolidayCalendar::poland();
+
+        assert_eq!(holidays.holiday_on(date(2010, 1, 6)), None);
+        assert_eq!(holidays.holiday_on(date(2011, 1, 6)), Some("Epiphany"));
+        assert_eq!(holidays.holiday_on(date(2024, 12, 24)), None);
+        assert_eq!(
+            holidays.holiday_on(date(2025, 12, 24)),
+            Some("Christmas Eve")
+        );
+        assert_eq!(holidays.holidays_in(2010).len(), 12);
+        assert_eq!(holidays.holidays_in(2024).len(), 13);
+        assert_eq!(holidays.holidays_in(2025).len(), 14);
+    }
+
+    #[test]
+    fn holidays_of_a_year_are_in_date_order() {
+        let calendar = HolidayCalendar::poland();
+        let holidays = calendar.holidays_in(2025);
+        let dates = holidays.iter().map(|(date, _)| *date).collect::<Vec<_>>();
+
+        assert!(dates.is_sorted());
+        assert_eq!(
+            holidays.first(),
+            Some(&(date(2025, 1, 1), "New Year's Day"))
+        );
+        assert_eq!(holidays[2], (date(2025, 4, 20), "Easter Sunday"));
+        assert_eq!(
+            holidays.last(),
+            Some(&(date(2025, 12, 26), "Second Day of Christmas"))
+        );
+    }
+
+    #[test]
+    fn settings_without_a_preset_have_no_holidays() {
+        assert_eq!(HolidayPreset::default(), HolidayPreset::None);
+        assert!(HolidayPreset::default().calendar().holidays.is_empty());
+    }
+}
@@ -0,0 +1,868 @@
+use super::calendar_state::CalendarDate;
+use super::day_data::{DayData, Session};
+use super::holidays::Holiday;
+use super::work_span::WorkSpan;
+use chrono::prelude::*;
+use chrono::{Days, Duration};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Longest day off read from a single event, so a mistyped end cannot fill years
+const MAX_EVENT_DAYS: u64 = 366;
+
+/// Start or end of an event in an iCalendar file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventTime {
+    /// Date of an all-day event
+    Date(NaiveDate),
+    /// Local time of a timed event
+    DateTime(NaiveDateTime),
+}
+
+impl Default for EventTime {
+    fn default() -> Self {
+        EventTime::Date(NaiveDate::default())
+    }
+}
+
+impl EventTime {
+    /// Start of the date or the time itself
+    fn date_time(self) -> NaiveDateTime {
+        match self {
+            EventTime::Date(date) => date.and_time(NaiveTime::MIN),
+            EventTime::DateTime(time) => time,
+        }
+    }
+}
+
+/// An event of an iCalendar file, with the properties the app reads
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub start: EventTime,
+    /// End of the event, exclusive; `None` if the file gives none
+    pub end: Option<EventTime>,
+    /// Recurrence rule, e.g. `FREQ=YEARLY;COUNT=5`
+    pub rrule: Option<String>,
+    /// Start of the occurrence of a recurring event this event replaces
+    pub recurrence_id: Option<EventTime>,
+    /// Starts of the occurrences left out of the recurrence
+    pub exdates: Vec<EventTime>,
+    /// Shown as free rather than busy
+    pub transparent: bool,
+    pub cancelled: bool,
+    /// E-mail addresses of the attendees who declined, in lower case
+    pub declined_by: Vec<String>,
+}
+
+/// Busy time of each day, merged from the meetings of an iCalendar file
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BusyTime {
+    /// A session for every stretch of back-to-back meetings, in date order
+    pub days: Vec<(CalendarDate, DayData)>,
+    /// Why each event left out was not counted
+    pub skipped: Vec<String>,
+}
+
+/// Days off read from an iCalendar file, with the events that could not be read as days off
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaysOffImport {
+    pub days_off: Vec<Holiday>,
+    /// Why each event left out was not imported
+    pub skipped: Vec<String>,
+}
+
+/// Read the events of an iCalendar file; events without a readable start are returned as errors
+#[cfg(feature = "ssr")]
+pub fn parse_events(content: &str) -> Result<Vec<Result<CalendarEvent, String>>, String> {
+    let mut events = Vec::new();
+    for calendar in ical::IcalParser::new(content.as_bytes()) {
+        let calendar = calendar.map_err(|e| e.to_string())?;
+        for event in calendar.events {
+            let properties = |name: &'static str| {
+                event
+                    .properties
+                    .iter()
+                    .filter(move |property| property.name.eq_ignore_ascii_case(name))
+            };
+            let property = |name| properties(name).find_map(|property| property.value.as_deref());
+            let summary = unescape(property("SUMMARY").unwrap_or_default());
+            let start = match property("DTSTART").map(parse_time) {
+                Some(Some(start)) => start,
+                Some(None) => {
+                    events.push(Err(format!("`{summary}` has an invalid start")));
+                    continue;
+                }
+                None => {
+                    events.push(Err(format!("`{summary}` has no start")));
+                    continue;
+                }
+            };
+            let duration_end = property("DURATION").map(|duration| {
+                parse_duration(duration)
+                    .and_then(|duration| start.date_time().checked_add_signed(duration))
+            });
+            let end = match (property("DTEND").and_then(parse_time), duration_end) {
+                (Some(end), _) => Some(end),
+                (None, Some(Some(end))) => Some(EventTime::DateTime(end)),
+                (None, Some(None)) => {
+                    events.push(Err(format!("`{summary}` has an invalid duration")));
+                    continue;
+                }
+                (None, None) => None,
+            };
+            let declined_by = properties("ATTENDEE")
+                .filter(|attendee| {
+                    attendee.params.iter().flatten().any(|(name, values)| {
+                        name.eq_ignore_ascii_case("PARTSTAT")
+                            && values
+                                .iter()
+                                .any(|value| value.eq_ignore_ascii_case("DECLINED"))
+                    })
+                })
+                .filter_map(|attendee| attendee.value.as_deref())
+                .map(|address| {
+                    let address = address.trim().to_lowercase();
+                    address
+                        .strip_prefix("mailto:")
+                        .map_or(address.clone(), str::to_string)
+                })
+                .collect();
+            events.push(Ok(CalendarEvent {
+                uid: property("UID").unwrap_or_default().to_string(),
+                start,
+                end,
+                rrule: property("RRULE").map(str::to_string),
+                recurrence_id: property("RECURRENCE-ID").and_then(parse_time),
+                exdates: properties("EXDATE")
+                    .filter_map(|property| property.value.as_deref())
+                    .flat_map(|dates| dates.split(','))
+                    .filter_map(parse_time)
+                    .collect(),
+                transparent: property("TRANSP").is_some_and(|transp| transp == "TRANSPARENT")
+                    || property("X-MICROSOFT-CDO-BUSYSTATUS")
+                        .is_some_and(|status| status == "FREE"),
+                cancelled: property("STATUS").is_some_and(|status| status == "CANCELLED"),
+                declined_by,
+                summary,
+            }));
+        }
+    }
+    Ok(events)
+}
+
+/// Date or time of a `DTSTART` or `DTEND` value; UTC times are moved to the local time zone
+fn parse_time(value: &str) -> Option<EventTime> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(EventTime::DateTime(
+            Utc.from_utc_datetime(&time)
+                .with_timezone(&Local)
+                .naive_local(),
+        ));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map(EventTime::DateTime)
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d").map(EventTime::Date))
+        .ok()
+}
+
+/// Length of a `DURATION` value, e.g. `PT1H30M` or `P1D`; `None` if it is invalid or too long
+#[cfg(feature = "ssr")]
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('P')?;
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        let unit = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'T' => continue,
+            'W' => Duration::weeks(1),
+            'D' => Duration::days(1),
+            'H' => Duration::hours(1),
+            'M' => Duration::minutes(1),
+            'S' => Duration::seconds(1),
+            _ => return None,
+        };
+        duration = duration.checked_add(&unit.checked_mul(number.parse().ok()?)?)?;
+        number.clear();
+    }
+    Some(duration)
+}
+
+/// Text value without the escaping of RFC 5545
+#[cfg(feature = "ssr")]
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Days off of all-day events, recurring every year when their rule says so
+pub fn days_off(events: Vec<Result<CalendarEvent, String>>) -> DaysOffImport {
+    let mut import = DaysOffImport::default();
+    for event in events {
+        match event.and_then(|event| event_days_off(&event)) {
+            Ok(days_off) => {
+                for day_off in days_off {
+                    if !import.days_off.contains(&day_off) {
+                        import.days_off.push(day_off);
+                    }
+                }
+            }
+            Err(reason) => import.skipped.push(reason),
+        }
+    }
+    import
+}
+
+/// A day off for each day an event covers
+fn event_days_off(event: &CalendarEvent) -> Result<Vec<Holiday>, String> {
+    let name = if event.summary.is_empty() {
+        "Day off"
+    } else {
+        event.summary.as_str()
+    };
+    if event.cancelled {
+        return Err(format!("`{name}` is cancelled"));
+    }
+    let EventTime::Date(start) = event.start else {
+        return Err(format!("`{name}` is not an all-day event"));
+    };
+    let days = match event.end {
+        Some(EventTime::Date(end)) if end > start => (end - start).num_days() as u64,
+        _ => 1,
+    };
+    if days > MAX_EVENT_DAYS {
+        return Err(format!("`{name}` lasts more than a year"));
+    }
+    let dates = start.iter_days().take(days as usize);
+
+    let Some(rrule) = &event.rrule else {
+        return Ok(dates.map(|date| Holiday::on(name, date)).collect());
+    };
+    let mut yearly = false;
+    let mut last_year = None;
+    for part in rrule.split(';') {
+        match part.split_once('=') {
+            Some(("FREQ", "YEARLY")) => yearly = true,
+            Some(("FREQ", _)) => yearly = false,
+            Some(("INTERVAL", "1")) => {}
+            Some(("INTERVAL", _)) => yearly = false,
+            Some(("COUNT", count)) => {
+                let year = count
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .and_then(|count| start.year().checked_add(count - 1))
+                    .ok_or_else(|| format!("`{name}` has an invalid recurrence count"))?;
+                last_year = Some(year);
+            }
+            Some(("UNTIL", until)) => {
+                let year = until
+                    .get(..4)
+                    .and_then(|year| year.parse::<i32>().ok())
+                    .ok_or_else(|| format!("`{name}` has an invalid recurrence end"))?;
+                last_year = Some(year);
+            }
+            // Calendars often spell out the month and day the event already starts on
+            Some(("BYMONTH", month)) if month.parse() == Ok(start.month()) => {}
+            Some(("BYMONTHDAY", day)) if day.parse() == Ok(start.day()) => {}
+            Some(("WKST", _)) => {}
+            _ => return Err(format!("`{name}` does not repeat on one date every year")),
+        }
+    }
+    if !yearly {
+        return Err(format!("`{name}` repeats other than every year"));
+    }
+    Ok(dates
+        .map(|date| {
+            // Days of an event spanning New Year recur a year later than its start
+            let holiday = Holiday::fixed(name, date.month(), date.day()).since(date.year());
+            match last_year {
+                Some(last_year) => {
+                    holiday.until(last_year.saturating_add(date.year() - start.year()))
+                }
+                None => holiday,
+            }
+        })
+        .collect())
+}
+
+/// Time taken by the meetings between two dates, merging overlapping ones and leaving out the
+/// events shown as free, cancelled or declined by `email`
+pub fn busy_time(
+    events: Vec<Result<CalendarEvent, String>>,
+    from: NaiveDate,
+    to: NaiveDate,
+    email: &str,
+) -> BusyTime {
+    let email = email.trim().to_lowercase();
+    let mut busy = BusyTime::default();
+    let events = events
+        .into_iter()
+        .filter_map(|event| event.map_err(|reason| busy.skipped.push(reason)).ok())
+        .collect::<Vec<_>>();
+    // Occurrences moved, cancelled or declined one by one replace those of their recurring event
+    let replaced = events
+        .iter()
+        .filter_map(|event| Some((event.uid.clone(), event.recurrence_id?.date_time())))
+        .collect::<Vec<_>>();
+    let events = events
+        .into_iter()
+        .filter(|event| !event.transparent && !event.cancelled)
+        .filter(|event| email.is_empty() || !event.declined_by.contains(&email));
+
+    let range_start = from.and_time(NaiveTime::MIN);
+    let range_end = to
+        .checked_add_days(Days::new(1))
+        .unwrap_or(to)
+        .and_time(NaiveTime::MIN);
+    // Minutes from midnight taken on each date, with the meetings taking them
+    let mut taken = BTreeMap::<NaiveDate, Vec<(u32, u32, String)>>::new();
+    for event in events {
+        let (EventTime::DateTime(start), Some(EventTime::DateTime(end))) = (event.start, event.end)
+        else {
+            // All-day events are rarely meetings, e.g. birthdays or someone's vacation
+            continue;
+        };
+        let starts = match occurrences(&event, start, range_end) {
+            Ok(starts) => starts,
+            Err(reason) => {
+                busy.skipped.push(reason);
+                continue;
+            }
+        };
+        let Some(occurrences) = starts
+            .into_iter()
+            .map(|occurrence| Some((occurrence, occurrence.checked_add_signed(end - start)?)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            busy.skipped
+                .push(format!("`{}` lasts too long", event.summary));
+            continue;
+        };
+        for (occurrence, occurrence_end) in occurrences {
+            let excluded = event
+                .exdates
+                .iter()
+                .any(|exdate| exdate.date_time() == occurrence)
+                || (event.recurrence_id.is_none()
+                    && replaced.contains(&(event.uid.clone(), occurrence)));
+            if excluded {
+                continue;
+            }
+            let mut piece_start = occurrence.max(range_start);
+            let occurrence_end = occurrence_end.min(range_end);
+            // Meetings running past midnight take time on both days
+            while piece_start < occurrence_end {
+                let date = piece_start.date();
+                let midnight = date
+                    .checked_add_days(Days::new(1))
+                    .unwrap_or(date)
+                    .and_time(NaiveTime::MIN);
+                let piece_end = occurrence_end.min(midnight);
+                let minutes = |time: NaiveDateTime| {
+                    (time - date.and_time(NaiveTime::MIN)).num_minutes() as u32
+                };
+                taken.entry(date).or_default().push((
+                    minutes(piece_start),
+                    minutes(piece_end),
+                    event.summary.clone(),
+                ));
+                piece_start = piece_end;
+            }
+        }
+    }
+
+    for (date, mut intervals) in taken {
+        intervals.sort_by_key(|&(start, end, _)| (start, end));
+        let mut merged: Vec<(u32, u32, Vec<String>)> = Vec::new();
+        for (start, end, summary) in intervals {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end, Vec::new())),
+            }
+            if let Some((_, _, summaries)) = merged.last_mut() {
+                if !summary.is_empty() && !summaries.contains(&summary) {
+                    summaries.push(summary);
+                }
+            }
+        }
+        let sessions = merged
+            .into_iter()
+            .filter(|(start, end, _)| end > start)
+            .map(|(start, end, summaries)| {
+                let time = |minutes: u32| {
+                    NaiveTime::from_hms_opt(minutes / 60 % 24, minutes % 60, 0).unwrap_or_default()
+                };
+                Session::from_span(WorkSpan {
+                    start: time(start),
+                    end: time(end),
+                    breaks: Vec::new(),
+                })
+                .with_note(summaries.join("; "))
+            })
+            .collect::<Vec<_>>();
+        if !sessions.is_empty() {
+            busy.days.push((
+                (date.year(), date.month(), date.day()),
+                DayData::from_sessions(sessions),
+            ));
+        }
+    }
+    busy
+}
+
+/// Starts of the occurrences of an event before `until`, following daily and weekly rules
+fn occurrences(
+    event: &CalendarEvent,
+    start: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Result<Vec<NaiveDateTime>, String> {
+    let Some(rrule) = &event.rrule else {
+        return Ok(vec![start]);
+    };
+    let unsupported = || format!("`{}` repeats in a way that is not supported", event.summary);
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut last = until;
+    let mut weekdays = Vec::new();
+    for part in rrule.split(';') {
+        match part.split_once('=') {
+            Some(("FREQ", value)) => frequency = Some(value),
+            Some(("INTERVAL", value)) => {
+                interval = value.parse::<u64>().map_err(|_| unsupported())?
+            }
+            Some(("COUNT", value)) => {
+                count = Some(value.parse::<usize>().map_err(|_| unsupported())?)
+            }
+            Some(("UNTIL", value)) => {
+                let end = parse_time(value).ok_or_else(unsupported)?;
+                // A date given as the end includes its whole day
+                let end = match end {
+                    EventTime::Date(date) => date
+                        .and_time(NaiveTime::MIN)
+                        .checked_add_signed(Duration::days(1)),
+                    EventTime::DateTime(time) => time.checked_add_signed(Duration::seconds(1)),
+                };
+                last = last.min(end.unwrap_or(NaiveDateTime::MAX));
+            }
+            Some(("BYDAY", value)) => {
+                for day in value.split(',') {
+                    weekdays.push(weekday(day).ok_or_else(unsupported)?);
+                }
+            }
+            Some(("WKST", _)) => {}
+            _ => return Err(unsupported()),
+        }
+    }
+    let interval = interval.max(1) as i64;
+
+    let candidates: Box<dyn Iterator<Item = NaiveDateTime>> = match frequency {
+        Some("DAILY") if weekdays.is_empty() => {
+            Box::new(std::iter::successors(Some(start), move |day| {
+                day.checked_add_signed(Duration::days(interval))
+            }))
+        }
+        Some("WEEKLY") => {
+            if weekdays.is_empty() {
+                weekdays.push(start.weekday());
+            }
+            weekdays.sort_by_key(Weekday::num_days_from_monday);
+            let first_week = start
+                .date()
+                .week(Weekday::Mon)
+                .first_day()
+                .and_time(start.time());
+            let weeks = std::iter::successors(Some(first_week), move |week| {
+                week.checked_add_signed(Duration::weeks(interval))
+            });
+            Box::new(weeks.flat_map(move |week| {
+                weekdays.clone().into_iter().filter_map(move |weekday| {
+                    week.checked_add_signed(Duration::days(weekday.num_days_from_monday() as i64))
+                })
+            }))
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(candidates
+        .filter(|occurrence| *occurrence >= start)
+        .take_while(|occurrence| *occurrence < last)
+        .take(count.unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// Weekday of a `BYDAY` code like `MO`; codes numbering the weekday within a month are not read
+fn weekday(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+
+    fn all_day(summary: &str, start: NaiveDate, days: u64, rrule: Option<&str>) -> CalendarEvent {
+        CalendarEvent {
+            summary: summary.to_string(),
+            start: EventTime::Date(start),
+            end: Some(EventTime::Date(start + Days::new(days))),
+            rrule: rrule.map(str::to_string),
+            ..CalendarEvent::default()
+        }
+    }
+
+    fn import(events: Vec<CalendarEvent>) -> DaysOffImport {
+        days_off(events.into_iter().map(Ok).collect())
+    }
+
+    #[test]
+    fn single_events_give_a_day_off_for_each_day_they_cover() {
+        let import = import(vec![
+            all_day("Team day", date(2025, 6, 13), 1, None),
+            all_day("Trip", date(2025, 8, 4), 3, None),
+        ]);
+        assert_eq!(
+            import.days_off,
+            vec![
+                Holiday::on("Team day", date(2025, 6, 13)),
+                Holiday::on("Trip", date(2025, 8, 4)),
+                Holiday::on("Trip", date(2025, 8, 5)),
+                Holiday::on("Trip", date(2025, 8, 6)),
+            ]
+        );
+        assert!(import.skipped.is_empty());
+    }
+
+    #[test]
+    fn yearly_events_recur_from_their_start_until_their_count_or_end() {
+        let import = import(vec![
+            all_day("Founding day", date(2020, 5, 4), 1, Some("FREQ=YEARLY")),
+            all_day(
+                "Summer party",
+                date(2024, 7, 19),
+                1,
+                Some("FREQ=YEARLY;COUNT=3"),
+            ),
+            all_day(
+                "Inventory",
+                date(2023, 2, 1),
+                1,
+                Some("FREQ=YEARLY;UNTIL=20261231T000000Z;BYMONTH=2;BYMONTHDAY=1"),
+            ),
+        ]);
+        assert_eq!(
+            import.days_off,
+            vec![
+                Holiday::fixed("Founding day", 5, 4).since(2020),
+                Holiday::fixed("Summer party", 7, 19)
+                    .since(2024)
+                    .until(2026),
+                Holiday::fixed("Inventory", 2, 1).since(2023).until(2026),
+            ]
+        );
+        assert!(import.skipped.is_empty());
+    }
+
+    #[test]
+    fn days_of_a_yearly_event_past_new_year_recur_in_the_following_year() {
+        let import = import(vec![all_day(
+            "Winter break",
+            date(2025, 12, 31),
+            2,
+            Some("FREQ=YEARLY;COUNT=2"),
+        )]);
+        assert_eq!(
+            import.days_off,
+            vec![
+                Holiday::fixed("Winter break", 12, 31)
+                    .since(2025)
+                    .until(2026),
+                Holiday::fixed("Winter break", 1, 1).since(2026).until(2027),
+            ]
+        );
+        assert_eq!(import.days_off[1].date_in(2026), Some(date(2026, 1, 1)));
+    }
+
+    #[test]
+    fn events_that_do_not_fall_on_the_same_date_every_year_are_skipped() {
+        let import = import(vec![
+            all_day(
+                "Thanksgiving",
+                date(2025, 11, 27),
+                1,
+                Some("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH"),
+            ),
+            all_day("Review", date(2025, 3, 3), 1, Some("FREQ=MONTHLY")),
+            all_day("Audit", date(2025, 3, 3), 1, Some("FREQ=YEARLY;INTERVAL=2")),
+            all_day(
+                "Forever",
+                date(2025, 3, 3),
+                1,
+                Some("FREQ=YEARLY;COUNT=2147483647"),
+            ),
+            all_day("Never", date(2025, 3, 3), 1, Some("FREQ=YEARLY;COUNT=0")),
+            CalendarEvent {
+                summary: "Stand-up".to_string(),
+                start: EventTime::DateTime(date(2025, 3, 3).and_hms_opt(9, 0, 0).unwrap()),
+                ..CalendarEvent::default()
+            },
+        ]);
+        assert!(import.days_off.is_empty());
+        assert_eq!(
+            import.skipped,
+            vec![
+                "`Thanksgiving` does not repeat on one date every year",
+                "`Review` repeats other than every year",
+                "`Audit` repeats other than every year",
+                "`Forever` has an invalid recurrence count",
+                "`Never` has an invalid recurrence count",
+                "`Stand-up` is not an all-day event",
+            ]
+        );
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        date(2025, 3, day).and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn meeting(summary: &str, start: NaiveDateTime, minutes: i64) -> CalendarEvent {
+        CalendarEvent {
+            uid: summary.to_string(),
+            summary: summary.to_string(),
+            start: EventTime::DateTime(start),
+            end: Some(EventTime::DateTime(start + Duration::minutes(minutes))),
+            ..CalendarEvent::default()
+        }
+    }
+
+    /// Spans and notes of the sessions suggested on each day
+    fn suggested(busy: &BusyTime) -> Vec<(CalendarDate, Vec<(String, String)>)> {
+        busy.days
+            .iter()
+            .map(|(date, day)| {
+                let sessions = day
+                    .sessions()
+                    .iter()
+                    .map(|session| {
+                        let span = session.span.as_ref().unwrap();
+                        let span = format!(
+                            "{}-{}",
+                            span.start.format("%H:%M"),
+                            span.end.format("%H:%M")
+                        );
+                        (span, session.note.clone())
+                    })
+                    .collect();
+                (*date, sessions)
+            })
+            .collect()
+    }
+
+    fn suggest(events: Vec<CalendarEvent>, from: u32, to: u32, email: &str) -> BusyTime {
+        busy_time(
+            events.into_iter().map(Ok).collect(),
+            date(2025, 3, from),
+            date(2025, 3, to),
+            email,
+        )
+    }
+
+    fn session(span: &str, note: &str) -> (String, String) {
+        (span.to_string(), note.to_string())
+    }
+
+    #[test]
+    fn overlapping_and_back_to_back_meetings_are_merged() {
+        let busy = suggest(
+            vec![
+                meeting("Planning", at(3, 9, 0), 60),
+                meeting("Review", at(3, 9, 30), 60),
+                meeting("Retro", at(3, 10, 30), 30),
+                meeting("Planning", at(3, 9, 45), 15),
+                meeting("1:1", at(3, 13, 0), 30),
+                meeting("Demo", at(5, 15, 0), 45),
+            ],
+            1,
+            31,
+            "",
+        );
+        assert_eq!(
+            suggested(&busy),
+            vec![
+                (
+                    (2025, 3, 3),
+                    vec![
+                        session("09:00-11:00", "Planning; Review; Retro"),
+                        session("13:00-13:30", "1:1"),
+                    ]
+                ),
+                ((2025, 3, 5), vec![session("15:00-15:45", "Demo")]),
+            ]
+        );
+        assert_eq!(busy.days[0].1.hours(), 2);
+        assert_eq!(busy.days[0].1.minutes(), 30);
+        assert!(busy.skipped.is_empty());
+    }
+
+    #[test]
+    fn meetings_past_midnight_are_split_and_cut_to_the_range() {
+        let events = || vec![meeting("Release", at(3, 23, 0), 120)];
+        assert_eq!(
+            suggested(&suggest(events(), 3, 4, "")),
+            vec![
+                ((2025, 3, 3), vec![session("23:00-00:00", "Release")]),
+                ((2025, 3, 4), vec![session("00:00-01:00", "Release")]),
+            ]
+        );
+        assert_eq!(
+            suggested(&suggest(events(), 4, 4, "")),
+            vec![((2025, 3, 4), vec![session("00:00-01:00", "Release")])]
+        );
+        assert!(suggest(events(), 5, 31, "").days.is_empty());
+    }
+
+    #[test]
+    fn free_cancelled_declined_and_all_day_events_are_left_out() {
+        let busy = suggest(
+            vec![
+                CalendarEvent {
+                    transparent: true,
+                    ..meeting("Focus time", at(3, 8, 0), 60)
+                },
+                CalendarEvent {
+                    cancelled: true,
+                    ..meeting("Sync", at(3, 10, 0), 60)
+                },
+                CalendarEvent {
+                    declined_by: vec!["me@example.com".to_string()],
+                    ..meeting("Offsite", at(3, 12, 0), 60)
+                },
+                CalendarEvent {
+                    declined_by: vec!["someone@example.com".to_string()],
+                    ..meeting("Kick-off", at(3, 14, 0), 60)
+                },
+                all_day("Conference", date(2025, 3, 3), 1, None),
+            ],
+            1,
+            31,
+            " Me@Example.com ",
+        );
+        assert_eq!(
+            suggested(&busy),
+            vec![((2025, 3, 3), vec![session("14:00-15:00", "Kick-off")])]
+        );
+    }
+
+    #[test]
+    fn recurring_meetings_leave_out_excluded_and_replaced_occurrences() {
+        let weekly = CalendarEvent {
+            rrule: Some("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5".to_string()),
+            exdates: vec![EventTime::DateTime(at(5, 10, 0))],
+            ..meeting("Stand-up", at(3, 10, 0), 15)
+        };
+        let moved = CalendarEvent {
+            recurrence_id: Some(EventTime::DateTime(at(10, 10, 0))),
+            ..meeting("Stand-up", at(11, 11, 0), 15)
+        };
+        let declined = CalendarEvent {
+            recurrence_id: Some(EventTime::DateTime(at(12, 10, 0))),
+            declined_by: vec!["me@example.com".to_string()],
+            ..meeting("Stand-up", at(12, 10, 0), 15)
+        };
+        let busy = suggest(vec![weekly, moved, declined], 1, 31, "me@example.com");
+        assert_eq!(
+            suggested(&busy),
+            vec![
+                ((2025, 3, 3), vec![session("10:00-10:15", "Stand-up")]),
+                ((2025, 3, 11), vec![session("11:00-11:15", "Stand-up")]),
+                ((2025, 3, 17), vec![session("10:00-10:15", "Stand-up")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn meetings_repeating_in_unsupported_ways_or_lasting_too_long_are_skipped() {
+        let busy = suggest(
+            vec![
+                CalendarEvent {
+                    rrule: Some("FREQ=MONTHLY;BYDAY=1MO".to_string()),
+                    ..meeting("Town hall", at(3, 16, 0), 60)
+                },
+                CalendarEvent {
+                    rrule: Some("FREQ=DAILY;COUNT=2".to_string()),
+                    end: Some(EventTime::DateTime(NaiveDateTime::MAX)),
+                    ..meeting("Endless", at(3, 9, 0), 0)
+                },
+            ],
+            1,
+            31,
+            "",
+        );
+        assert!(busy.days.is_empty());
+        assert_eq!(
+            busy.skipped,
+            vec![
+                "`Town hall` repeats in a way that is not supported",
+                "`Endless` lasts too long",
+            ]
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn durations_give_the_end_and_are_checked() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+             BEGIN:VEVENT\r\nUID:a\r\nSUMMARY:Workshop\r\nDTSTART:20250303T090000\r\n\
+             DURATION:PT1H30M\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:b\r\nSUMMARY:Sabbatical\r\nDTSTART:20250303T090000\r\n\
+             DURATION:P2000000000W\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:c\r\nSUMMARY:Typo\r\nDTSTART:20250303T090000\r\n\
+             DURATION:PT1X\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            events[0].as_ref().unwrap().end,
+            Some(EventTime::DateTime(at(3, 10, 30)))
+        );
+        assert_eq!(
+            events[1],
+            Err("`Sabbatical` has an invalid duration".to_string())
+        );
+        assert_eq!(events[2], Err("`Typo` has an invalid duration".to_string()));
+        assert_eq!(
+            parse_duration("P1W2DT3H4M5S"),
+            Some(Duration::seconds(788_645))
+        );
+        assert_eq!(parse_duration("P9999999999999999W"), None);
+    }
+}
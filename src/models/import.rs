@@ -0,0 +1,310 @@
+use super::{
+    calendar_state::CalendarDate,
+    day_data::{DayData, Session},
+    export::{DecimalSeparator, Delimiter},
+};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Date formats accepted in imported files
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y"];
+
+/// What to do with an imported day whose date already has an entry
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictMode {
+    /// Keep the entry already logged
+    #[default]
+    Skip,
+    /// Replace the entry already logged
+    Overwrite,
+    /// Add the imported time as another session
+    Merge,
+}
+
+impl ConflictMode {
+    pub const ALL: [ConflictMode; 3] = [
+        ConflictMode::Skip,
+        ConflictMode::Overwrite,
+        ConflictMode::Merge,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictMode::Skip => "Keep the logged entry",
+            ConflictMode::Overwrite => "Overwrite it",
+            ConflictMode::Merge => "Add the imported time to it",
+        }
+    }
+}
+
+/// Columns holding each value, counted from 0
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub date: Option<usize>,
+    pub hours: Option<usize>,
+    pub minutes: Option<usize>,
+    /// Hours with a fractional part, read instead of `hours` and `minutes` when set
+    pub decimal_hours: Option<usize>,
+    pub note: Option<usize>,
+}
+
+impl ColumnMapping {
+    /// Guess the columns from the names in the header row
+    pub fn guess(headers: &[String]) -> Self {
+        let find = |names: &[&str]| {
+            headers.iter().position(|header| {
+                let header = header.trim().to_lowercase();
+                names.iter().any(|name| header == *name)
+            })
+        };
+        let mut mapping = Self {
+            date: find(&["date", "day", "data", "dzień"]),
+            hours: find(&["hours", "hour", "godziny"]),
+            minutes: find(&["minutes", "minute", "minuty"]),
+            decimal_hours: find(&["decimal hours", "hours (decimal)"]),
+            note: find(&["note", "notes", "comment", "description", "uwagi", "opis"]),
+        };
+        // A single hours column usually holds decimal hours, like the CSV export does
+        if mapping.decimal_hours.is_none() && mapping.minutes.is_none() {
+            mapping.decimal_hours = mapping.hours.take();
+        }
+        mapping
+    }
+}
+
+/// How to read a CSV file to import; the first row names the columns
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportOptions {
+    pub delimiter: Delimiter,
+    pub decimal: DecimalSeparator,
+    /// Columns to read, guessed from the header row when `None`
+    pub mapping: Option<ColumnMapping>,
+}
+
+/// A row of an imported file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImportRow {
+    /// Line in the file, counted from 1
+    pub line: u64,
+    /// The day read from the row, or why it could not be read
+    pub entry: Result<(CalendarDate, DayData), String>,
+    /// Entry already logged on the date
+    pub existing: Option<DayData>,
+}
+
+/// An imported file as it would be read, before anything is stored
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub mapping: ColumnMapping,
+    pub rows: Vec<ImportRow>,
+}
+
+/// Days stored by an import, by what happened to their date
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub overwritten: usize,
+    pub merged: usize,
+    pub skipped: usize,
+    /// Rows that could not be read, or days that would exceed 24 hours when merged
+    pub failed: usize,
+}
+
+/// Read a day from the fields of a row
+pub fn parse_row(
+    fields: &[String],
+    mapping: &ColumnMapping,
+    decimal: DecimalSeparator,
+) -> Result<(CalendarDate, DayData), String> {
+    let field = |column: Option<usize>| {
+        column.map(|column| fields.get(column).map_or("", |field| field.trim()))
+    };
+
+    let date = field(mapping.date).ok_or("no column is mapped to the date")?;
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| format!("invalid date `{date}`"))?;
+
+    let day = match field(mapping.decimal_hours) {
+        Some(hours) => {
+            let value = decimal
+                .parse(hours)
+                .filter(|value| *value >= 0.0)
+                .ok_or_else(|| format!("invalid hours `{hours}`"))?;
+            DayData::from_hours(value)
+        }
+        None if mapping.hours.is_none() && mapping.minutes.is_none() => {
+            return Err("no column is mapped to the hours".to_string());
+        }
+        None => {
+            let whole = |value: Option<&str>, name: &str| match value {
+                None | Some("") => Ok(0),
+                Some(value) => value
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid {name} `{value}`")),
+            };
+            let hours = whole(field(mapping.hours), "hours")?;
+            let minutes = whole(field(mapping.minutes), "minutes")?;
+            let hours = hours
+                .checked_add(minutes / 60)
+                .ok_or("a day cannot log more than 24 hours")?;
+            DayData::new(hours, minutes % 60)
+        }
+    };
+    if day.to_hours() > 24.0 {
+        return Err("a day cannot log more than 24 hours".to_string());
+    }
+
+    let note = field(mapping.note).unwrap_or_default();
+    let session = Session::new(day.hours(), day.minutes()).with_note(note);
+    Ok((
+        (date.year(), date.month(), date.day()),
+        DayData::from_sessions(vec![session]),
+    ))
+}
+
+/// Day logging the sessions of both entries, keeping the type of the first
+pub fn merge_days(first: &DayData, second: &DayData) -> Result<DayData, &'static str> {
+    let sessions = first
+        .sessions()
+        .iter()
+        .chain(second.sessions())
+        .cloned()
+        .collect();
+    let merged = DayData::from_sessions(sessions).with_kind(first.kind());
+    if merged.to_hours() > 24.0 {
+        return Err("a day cannot log more than 24 hours");
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::day_data::EntryKind;
+
+    fn fields(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn columns(date: usize, hours: usize, minutes: usize, note: usize) -> ColumnMapping {
+        ColumnMapping {
+            date: Some(date),
+            hours: Some(hours),
+            minutes: Some(minutes),
+            decimal_hours: None,
+            note: Some(note),
+        }
+    }
+
+    #[test]
+    fn columns_are_guessed_from_the_header_names() {
+        let mapping = ColumnMapping::guess(&fields(&["Data", " Godziny ", "Minuty", "Uwagi"]));
+        assert_eq!(mapping, columns(0, 1, 2, 3));
+
+        let mapping = ColumnMapping::guess(&fields(&["Note", "Date", "Hours"]));
+        assert_eq!(
+            mapping,
+            ColumnMapping {
+                date: Some(1),
+                hours: None,
+                minutes: None,
+                decimal_hours: Some(2),
+                note: Some(0),
+            }
+        );
+        assert_eq!(
+            ColumnMapping::guess(&fields(&["when", "how long"])),
+            ColumnMapping::default()
+        );
+    }
+
+    #[test]
+    fn rows_are_read_in_each_date_format_and_hours_layout() {
+        let mapping = columns(0, 1, 2, 3);
+        let (date, day) = parse_row(
+            &fields(&["07.03.2025", "7", "90", " on site "]),
+            &mapping,
+            DecimalSeparator::Point,
+        )
+        .unwrap();
+        assert_eq!(date, (2025, 3, 7));
+        assert_eq!((day.hours(), day.minutes()), (8, 30));
+        assert_eq!(day.sessions()[0].note, "on site");
+
+        let decimal = ColumnMapping {
+            date: Some(0),
+            decimal_hours: Some(1),
+            ..ColumnMapping::default()
+        };
+        let (date, day) = parse_row(
+            &fields(&["07/03/2025", "7,75"]),
+            &decimal,
+            DecimalSeparator::Comma,
+        )
+        .unwrap();
+        assert_eq!(date, (2025, 3, 7));
+        assert_eq!((day.hours(), day.minutes()), (7, 45));
+
+        let (_, day) = parse_row(
+            &fields(&["2025-03-07", "", "45"]),
+            &mapping,
+            DecimalSeparator::Point,
+        )
+        .unwrap();
+        assert_eq!((day.hours(), day.minutes()), (0, 45));
+    }
+
+    #[test]
+    fn rows_that_cannot_be_read_say_why() {
+        let mapping = columns(0, 1, 2, 3);
+        let error = |values: &[&str], mapping: &ColumnMapping| {
+            parse_row(&fields(values), mapping, DecimalSeparator::Point).unwrap_err()
+        };
+        assert_eq!(
+            error(&["2025-02-30", "8"], &mapping),
+            "invalid date `2025-02-30`"
+        );
+        assert_eq!(error(&["2025-03-07", "-1"], &mapping), "invalid hours `-1`");
+        assert_eq!(
+            error(&["2025-03-07", "8", "x"], &mapping),
+            "invalid minutes `x`"
+        );
+        assert_eq!(
+            error(&["2025-03-07", "24", "30"], &mapping),
+            "a day cannot log more than 24 hours"
+        );
+        assert_eq!(
+            error(&["2025-03-07", "4294967295", "4294967295"], &mapping),
+            "a day cannot log more than 24 hours"
+        );
+        assert_eq!(
+            error(&["2025-03-07"], &ColumnMapping::default()),
+            "no column is mapped to the date"
+        );
+        let date_only = ColumnMapping {
+            date: Some(0),
+            ..ColumnMapping::default()
+        };
+        assert_eq!(
+            error(&["2025-03-07"], &date_only),
+            "no column is mapped to the hours"
+        );
+    }
+
+    #[test]
+    fn merged_days_keep_both_sessions_and_the_first_type() {
+        let first = DayData::new(6, 0).with_kind(EntryKind::BusinessTrip);
+        let merged = merge_days(&first, &DayData::new(2, 30)).unwrap();
+        assert_eq!(merged.kind(), EntryKind::BusinessTrip);
+        assert_eq!(merged.sessions().len(), 2);
+        assert_eq!((merged.hours(), merged.minutes()), (8, 30));
+
+        assert_eq!(
+            merge_days(&DayData::new(20, 0), &DayData::new(4, 1)).unwrap_err(),
+            "a day cannot log more than 24 hours"
+        );
+    }
+}
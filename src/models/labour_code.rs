@@ -0,0 +1,164 @@
+//! Working time norm ("wymiar czasu pracy") under the Polish Labour Code, Art. 130
+
+use super::holidays::HolidayCalendar;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Hours in a full-time working day
+pub const DAILY_NORM_HOURS: f32 = 8.0;
+
+/// Norm of the month shown in the calendar header; balances always follow the work schedule
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormMode {
+    /// Sum of the hours expected by the personal work schedule
+    #[default]
+    Schedule,
+    /// Statutory norm of the Polish Labour Code
+    LabourCode,
+}
+
+impl NormMode {
+    pub const ALL: [NormMode; 2] = [NormMode::Schedule, NormMode::LabourCode];
+
+    /// Label shown in the settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            NormMode::Schedule => "Work schedule",
+            NormMode::LabourCode => "Polish Labour Code norm",
+        }
+    }
+}
+
+/// Settlement period ("okres rozliczeniowy") of a number of months, counted from January
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct SettlementPeriod {
+    months: u32,
+}
+
+impl SettlementPeriod {
+    /// Allowed lengths in months, all of which split a year evenly
+    pub const LENGTHS: [u32; 6] = [1, 2, 3, 4, 6, 12];
+
+    /// Create a period of `months` months, `None` unless it is one of [`Self::LENGTHS`]
+    pub fn new(months: u32) -> Option<Self> {
+        Self::LENGTHS.contains(&months).then_some(Self { months })
+    }
+
+    /// Length of the period in months
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    /// First and last day of the period containing a month
+    pub fn bounds(&self, year: i32, month: u32) -> (NaiveDate, NaiveDate) {
+        let first_month = (month - 1) / self.months * self.months + 1;
+        let last_month = first_month + self.months - 1;
+        let start = NaiveDate::from_ymd_opt(year, first_month, 1).unwrap();
+        let end = if last_month == 12 {
+            NaiveDate::from_ymd_opt(year, 12, 31).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(year, last_month + 1, 1)
+                .unwrap()
+                .pred_opt()
+                .unwrap()
+        };
+        (start, end)
+    }
+}
+
+impl TryFrom<u32> for SettlementPeriod {
+    type Error = String;
+
+    fn try_from(months: u32) -> Result<Self, Self::Error> {
+        Self::new(months).ok_or_else(|| format!("invalid settlement period of {months} months"))
+    }
+}
+
+impl From<SettlementPeriod> for u32 {
+    fn from(period: SettlementPeriod) -> Self {
+        period.months
+    }
+}
+
+impl Default for SettlementPeriod {
+    fn default() -> Self {
+        Self { months: 1 }
+    }
+}
+
+/// Statutory norm between two dates, both inclusive.
+///
+/// 40 hours for each full week plus 8 hours for each Monday to Friday of the remaining days comes
+/// down to 8 hours for every Monday to Friday. Each holiday falling on a day other than Sunday then
+/// lowers the norm by 8 hours, which is how a Saturday holiday reduces it too.
+pub fn statutory_norm_hours(from: NaiveDate, to: NaiveDate, holidays: &HolidayCalendar) -> f32 {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let weekday = date.weekday();
+            let is_workday = !matches!(weekday, Weekday::Sat | Weekday::Sun);
+            let is_reducing_holiday =
+                weekday != Weekday::Sun && holidays.holiday_on(date).is_some();
+            match (is_workday, is_reducing_holiday) {
+                (true, false) => DAILY_NORM_HOURS,
+                (false, true) => -DAILY_NORM_HOURS,
+                _ => 0.0,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+
+    #[test]
+    fn saturday_holiday_lowers_the_norm() {
+        // All Saints' Day on Saturday 1 and Independence Day on Tuesday 11 November 2025
+        let norm = statutory_norm_hours(
+            date(2025, 11, 1),
+            date(2025, 11, 30),
+            &HolidayCalendar::poland(),
+        );
+
+        assert_eq!(norm, 18.0 * 8.0);
+    }
+
+    #[test]
+    fn sunday_holiday_does_not_lower_the_norm() {
+        // Easter Sunday, 20 April 2025, and Easter Monday
+        let norm = statutory_norm_hours(
+            date(2025, 4, 1),
+            date(2025, 4, 30),
+            &HolidayCalendar::poland(),
+        );
+
+        assert_eq!(norm, 21.0 * 8.0);
+    }
+
+    #[test]
+    fn settlement_period_norm_sums_its_months() {
+        let period = SettlementPeriod::new(3).unwrap();
+        let (start, end) = period.bounds(2025, 11);
+
+        assert_eq!((start, end), (date(2025, 10, 1), date(2025, 12, 31)));
+        assert_eq!(
+            statutory_norm_hours(start, end, &HolidayCalendar::poland()),
+            184.0 + 144.0 + 160.0
+        );
+    }
+
+    #[test]
+    fn settlement_periods_are_counted_from_january() {
+        let bounds =
+            |months: u32, month: u32| SettlementPeriod::new(months).unwrap().bounds(2024, month);
+
+        assert_eq!(bounds(1, 2), (date(2024, 2, 1), date(2024, 2, 29)));
+        assert_eq!(bounds(2, 12), (date(2024, 11, 1), date(2024, 12, 31)));
+        assert_eq!(bounds(4, 5), (date(2024, 5, 1), date(2024, 8, 31)));
+        assert_eq!(bounds(12, 7), (date(2024, 1, 1), date(2024, 12, 31)));
+        assert_eq!(SettlementPeriod::new(5), None);
+    }
+}
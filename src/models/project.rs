@@ -0,0 +1,91 @@
+use super::{billing::HourlyRate, day_data::DayData};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Something hours are billed to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+    pub id: u64,
+    pub name: String,
+    /// CSS colour, e.g. `#3a7bd5`
+    pub colour: String,
+    /// Inactive projects are kept for past entries but cannot be picked for new ones
+    pub active: bool,
+    /// Who invoices for the project are addressed to
+    #[serde(default)]
+    pub client: String,
+    #[serde(default)]
+    pub rates: Vec<HourlyRate>,
+}
+
+impl Project {
+    /// Rate in effect on a date, `None` before the first one
+    pub fn rate_on(&self, date: NaiveDate) -> Option<&HourlyRate> {
+        self.rates
+            .iter()
+            .filter(|rate| rate.from <= date)
+            .max_by_key(|rate| rate.from)
+    }
+}
+
+/// Check that a colour is written as `#rrggbb`, the only form project colours are stored in
+pub fn is_hex_colour(colour: &str) -> bool {
+    colour
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Minutes logged per project over some days, most minutes first; `None` collects the time not
+/// billed to any project
+pub fn project_totals<'a>(days: impl IntoIterator<Item = &'a DayData>) -> Vec<(Option<u64>, u32)> {
+    let mut totals = HashMap::<Option<u64>, u32>::new();
+    for session in days.into_iter().flat_map(DayData::sessions) {
+        *totals.entry(session.project).or_default() += session.total_minutes();
+    }
+    let mut totals = totals
+        .into_iter()
+        .filter(|(_, minutes)| *minutes > 0)
+        .collect::<Vec<_>>();
+    totals.sort_by(|(a_project, a_minutes), (b_project, b_minutes)| {
+        b_minutes.cmp(a_minutes).then(a_project.cmp(b_project))
+    });
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::day_data::Session;
+
+    #[test]
+    fn totals_add_up_sessions_per_project_most_minutes_first() {
+        let days = [
+            DayData::from_sessions(vec![
+                Session::new(2, 30).with_project(Some(1)),
+                Session::new(1, 30),
+                Session::new(0, 45).with_project(Some(2)),
+            ]),
+            DayData::from_sessions(vec![
+                Session::new(1, 15).with_project(Some(2)),
+                Session::new(0, 0).with_project(Some(3)).with_note("call"),
+            ]),
+            DayData::new(0, 30),
+        ];
+        assert_eq!(
+            project_totals(&days),
+            vec![(Some(1), 150), (None, 120), (Some(2), 120)]
+        );
+    }
+
+    #[test]
+    fn colours_must_be_six_hex_digits() {
+        assert!(is_hex_colour("#3a7bd5"));
+        assert!(is_hex_colour("#FFFFFF"));
+        assert!(!is_hex_colour("3a7bd5"));
+        assert!(!is_hex_colour("#fff"));
+        assert!(!is_hex_colour("#3a7bd5; color: red"));
+        assert!(!is_hex_colour("red"));
+        assert!(!is_hex_colour("#ééé"));
+    }
+}
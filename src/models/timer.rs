@@ -0,0 +1,235 @@
+use super::calendar_state::CalendarDate;
+use super::day_data::{DayData, Session};
+use super::work_span::WorkSpan;
+use chrono::prelude::*;
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A stretch of time the timer ran, open while it is still running
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerRun {
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+}
+
+/// Stopwatch whose runs are logged as sessions when it is stopped
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timer {
+    pub runs: Vec<TimerRun>,
+}
+
+/// What the stopwatch shows
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerStatus {
+    pub running: bool,
+    /// Seconds run so far, as of the moment the status was taken
+    pub elapsed_seconds: i64,
+}
+
+impl Timer {
+    /// Check whether the last run is still open
+    pub fn is_running(&self) -> bool {
+        self.runs.last().is_some_and(|run| run.end.is_none())
+    }
+
+    /// Open a new run, unless one is open already
+    pub fn start(&mut self, now: NaiveDateTime) {
+        if !self.is_running() {
+            self.runs.push(TimerRun {
+                start: now,
+                end: None,
+            });
+        }
+    }
+
+    /// Close the open run
+    pub fn pause(&mut self, now: NaiveDateTime) {
+        if let Some(run) = self.runs.last_mut().filter(|run| run.end.is_none()) {
+            run.end = Some(now.max(run.start));
+        }
+    }
+
+    /// Seconds run so far, counting an open run up to `now`
+    pub fn elapsed_seconds(&self, now: NaiveDateTime) -> i64 {
+        self.runs
+            .iter()
+            .map(|run| (run.end.unwrap_or(now) - run.start).num_seconds().max(0))
+            .sum()
+    }
+
+    /// Status of the timer as of `now`
+    pub fn status(&self, now: NaiveDateTime) -> TimerStatus {
+        TimerStatus {
+            running: self.is_running(),
+            elapsed_seconds: self.elapsed_seconds(now),
+        }
+    }
+
+    /// Sessions the runs amount to, to the minute, with runs going past midnight split between
+    /// the days they cover; an open run is counted up to `now`
+    pub fn sessions(&self, now: NaiveDateTime) -> Vec<(CalendarDate, Session)> {
+        let mut sessions = Vec::new();
+        for run in &self.runs {
+            let end = truncate_to_minute(run.end.unwrap_or(now));
+            let mut start = truncate_to_minute(run.start);
+            while start < end {
+                let next_midnight = (start.date() + TimeDelta::days(1)).and_time(NaiveTime::MIN);
+                let piece_end = end.min(next_midnight);
+                let span = WorkSpan {
+                    start: start.time(),
+                    end: piece_end.time(),
+                    breaks: Vec::new(),
+                };
+                let date = start.date();
+                sessions.push((
+                    (date.year(), date.month(), date.day()),
+                    Session::from_span(span),
+                ));
+                start = piece_end;
+            }
+        }
+        sessions
+    }
+}
+
+/// Day with a session of the stopwatch added, continuing the day's last session when the new one
+/// starts as it ends; the session is cut short where the day would log more than 24 hours
+pub fn log_session(day: Option<&DayData>, session: Session) -> DayData {
+    let day = day.cloned().unwrap_or_default();
+    let logged_minutes = day.hours().saturating_mul(60).saturating_add(day.minutes());
+    let Some(session) = cut_short(session, MINUTES_PER_DAY.saturating_sub(logged_minutes)) else {
+        return day;
+    };
+    let mut sessions = day.sessions().to_vec();
+    if !sessions
+        .last_mut()
+        .is_some_and(|last| last.extend_with(&session))
+    {
+        sessions.push(session);
+    }
+    DayData::from_sessions(sessions).with_kind(day.kind())
+}
+
+/// Session of the stopwatch shortened to at most `minutes`, `None` if no time is left for it
+fn cut_short(session: Session, minutes: u32) -> Option<Session> {
+    if session.total_minutes() <= minutes {
+        return Some(session);
+    }
+    if minutes == 0 {
+        return None;
+    }
+    Some(match session.span {
+        Some(span) => Session::from_span(WorkSpan {
+            end: span.start + TimeDelta::minutes(minutes.into()),
+            breaks: Vec::new(),
+            ..span
+        }),
+        None => Session::new(minutes / 60, minutes % 60),
+    })
+}
+
+fn truncate_to_minute(time: NaiveDateTime) -> NaiveDateTime {
+    time.with_second(0)
+        .and_then(|time| time.with_nanosecond(0))
+        .unwrap_or(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, second)
+            .unwrap()
+    }
+
+    fn span(start: (u32, u32), end: (u32, u32)) -> WorkSpan {
+        WorkSpan {
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            breaks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_run_past_midnight_is_split_between_the_days() {
+        let timer = Timer {
+            runs: vec![TimerRun {
+                start: at(3, 23, 30, 15),
+                end: Some(at(4, 0, 45, 40)),
+            }],
+        };
+        let sessions = timer.sessions(at(4, 9, 0, 0));
+        assert_eq!(
+            sessions,
+            vec![
+                ((2025, 3, 3), Session::from_span(span((23, 30), (0, 0)))),
+                ((2025, 3, 4), Session::from_span(span((0, 0), (0, 45)))),
+            ]
+        );
+        assert_eq!(sessions[0].1.total_minutes(), 30);
+        assert_eq!(sessions[1].1.total_minutes(), 45);
+    }
+
+    #[test]
+    fn an_open_run_is_counted_up_to_now() {
+        let mut timer = Timer::default();
+        timer.start(at(3, 8, 0, 0));
+        timer.pause(at(3, 9, 0, 0));
+        timer.start(at(3, 10, 0, 0));
+
+        assert_eq!(timer.elapsed_seconds(at(3, 10, 30, 0)), 90 * 60);
+        assert_eq!(
+            timer.sessions(at(3, 10, 30, 0)),
+            vec![
+                ((2025, 3, 3), Session::from_span(span((8, 0), (9, 0)))),
+                ((2025, 3, 3), Session::from_span(span((10, 0), (10, 30)))),
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_within_a_minute_leave_no_session() {
+        let timer = Timer {
+            runs: vec![
+                TimerRun {
+                    start: at(3, 8, 0, 10),
+                    end: Some(at(3, 8, 0, 50)),
+                },
+                TimerRun {
+                    start: at(3, 9, 0, 50),
+                    end: Some(at(3, 9, 1, 10)),
+                },
+            ],
+        };
+        assert_eq!(
+            timer.sessions(at(3, 10, 0, 0)),
+            vec![((2025, 3, 3), Session::from_span(span((9, 0), (9, 1))))]
+        );
+    }
+
+    #[test]
+    fn a_logged_session_continues_the_last_one_when_it_starts_as_that_ends() {
+        let day = log_session(None, Session::from_span(span((8, 0), (9, 0))));
+        let day = log_session(Some(&day), Session::from_span(span((9, 0), (10, 0))));
+        assert_eq!(day.sessions(), [Session::from_span(span((8, 0), (10, 0)))]);
+
+        let day = log_session(Some(&day), Session::from_span(span((11, 0), (11, 30))));
+        assert_eq!(day.sessions().len(), 2);
+    }
+
+    #[test]
+    fn a_logged_session_is_cut_short_where_the_day_would_pass_24_hours() {
+        let day = DayData::new(20, 0);
+        let day = log_session(Some(&day), Session::from_span(span((0, 0), (6, 0))));
+        assert_eq!((day.hours(), day.minutes()), (24, 0));
+        assert_eq!(day.sessions()[1], Session::from_span(span((0, 0), (4, 0))));
+
+        let full = log_session(Some(&day), Session::from_span(span((6, 0), (7, 0))));
+        assert_eq!(full.sessions(), day.sessions());
+    }
+}
@@ -0,0 +1,31 @@
+use super::billing::BillingDetails;
+use super::flexitime::FlexitimeAccount;
+use super::holidays::{Holiday, HolidayPreset};
+use super::labour_code::{NormMode, SettlementPeriod};
+use super::vacation::VacationAllowance;
+use super::work_schedule::WorkSchedule;
+use serde::{Deserialize, Serialize};
+
+/// Settings of one person logging hours, kept per user
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub work_schedule: WorkSchedule,
+    #[serde(default)]
+    pub holiday_preset: HolidayPreset,
+    /// Company days off on top of the public holidays, imported from iCalendar files
+    #[serde(default)]
+    pub days_off: Vec<Holiday>,
+    #[serde(default)]
+    pub norm_mode: NormMode,
+    #[serde(default)]
+    pub settlement_period: SettlementPeriod,
+    #[serde(default)]
+    pub flexitime: FlexitimeAccount,
+    #[serde(default)]
+    pub vacation: VacationAllowance,
+    #[serde(default)]
+    pub billing: BillingDetails,
+    /// Name printed on timesheets above the employee's signature
+    #[serde(default)]
+    pub employee_name: String,
+}
@@ -0,0 +1,196 @@
+use super::calendar_state::CalendarDate;
+use super::day_data::{DayData, EntryKind};
+use super::work_calendar::WorkCalendar;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Yearly vacation entitlement
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VacationAllowance {
+    /// Vacation days granted for a full year
+    pub annual_days: f32,
+    /// First year tracked, `None` for the year being looked at
+    pub first_year: Option<i32>,
+    /// Days carried into the first year tracked
+    pub carried_over_days: f32,
+    /// Most days left at the end of a year that carry over into the next, `None` for no limit
+    #[serde(default)]
+    pub carry_over_limit: Option<f32>,
+    /// Start of employment; the allowance of that year is pro-rated by the months left
+    pub employment_start: Option<NaiveDate>,
+}
+
+impl VacationAllowance {
+    /// Days granted for a year, rounded up to a whole day when pro-rated
+    pub fn entitlement(&self, year: i32) -> f32 {
+        match self.employment_start {
+            Some(start) if year < start.year() => 0.0,
+            Some(start) if year == start.year() => {
+                (self.annual_days * (13 - start.month()) as f32 / 12.0).ceil()
+            }
+            _ => self.annual_days,
+        }
+    }
+
+    /// Days carried into the next year out of those left at the end of a year; an overdrawn
+    /// allowance is always carried in full
+    pub fn carry_over(&self, days_left: f32) -> f32 {
+        match self.carry_over_limit {
+            Some(limit) => days_left.min(limit),
+            None => days_left,
+        }
+    }
+}
+
+impl Default for VacationAllowance {
+    fn default() -> Self {
+        Self {
+            annual_days: 26.0,
+            first_year: None,
+            carried_over_days: 0.0,
+            carry_over_limit: None,
+            employment_start: None,
+        }
+    }
+}
+
+/// Vacation days of a year
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VacationLedger {
+    pub year: i32,
+    /// Days granted for the year
+    pub entitlement: f32,
+    /// Days left over from the year before, negative if it was overdrawn
+    pub carried_over: f32,
+    /// Days taken in the year
+    pub used: f32,
+}
+
+impl VacationLedger {
+    /// Days still available in the year
+    pub fn remaining(&self) -> f32 {
+        self.entitlement + self.carried_over - self.used
+    }
+}
+
+/// Vacation days taken in a year: a day for each vacation entry on a day expecting work, half a
+/// day for each half-day vacation
+pub fn vacation_days_used(
+    year: i32,
+    entries: &[(CalendarDate, DayData)],
+    work_calendar: &WorkCalendar,
+) -> f32 {
+    entries
+        .iter()
+        .filter(|((entry_year, _, _), _)| *entry_year == year)
+        .filter_map(|((year, month, day), data)| {
+            let date = NaiveDate::from_ymd_opt(*year, *month, *day)?;
+            if work_calendar.expected_hours(date) == 0.0 {
+                return None;
+            }
+            match data.kind() {
+                EntryKind::Vacation => Some(1.0),
+                EntryKind::HalfDayVacation => Some(0.5),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+/// Ledger of a year, carrying what is left of each year tracked into the next one
+pub fn vacation_ledger(
+    allowance: &VacationAllowance,
+    year: i32,
+    entries: &[(CalendarDate, DayData)],
+    work_calendar: &WorkCalendar,
+) -> VacationLedger {
+    let first_year = allowance.first_year.unwrap_or(year);
+    let carried_over = if year < first_year {
+        0.0
+    } else {
+        (first_year..year).fold(allowance.carried_over_days, |carried_over, earlier_year| {
+            allowance.carry_over(
+                allowance.entitlement(earlier_year) + carried_over
+                    - vacation_days_used(earlier_year, entries, work_calendar),
+            )
+        })
+    };
+
+    VacationLedger {
+        year,
+        entitlement: allowance.entitlement(year),
+        carried_over,
+        used: vacation_days_used(year, entries, work_calendar),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::date;
+
+    /// A vacation entry on each of the first `count` working days of a year
+    fn vacation_days(year: i32, count: usize) -> Vec<(CalendarDate, DayData)> {
+        let work_calendar = WorkCalendar::four_day_week();
+        date(year, 1, 1)
+            .iter_days()
+            .filter(|date| work_calendar.expected_hours(*date) > 0.0)
+            .take(count)
+            .map(|date| {
+                (
+                    (date.year(), date.month(), date.day()),
+                    DayData::default().with_kind(EntryKind::Vacation),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn year_of_the_employment_start_is_pro_rated() {
+        let allowance = VacationAllowance {
+            employment_start: Some(date(2025, 4, 15)),
+            ..VacationAllowance::default()
+        };
+
+        assert_eq!(allowance.entitlement(2024), 0.0);
+        // 26 days for the 9 months from April is 19.5, rounded up
+        assert_eq!(allowance.entitlement(2025), 20.0);
+        assert_eq!(allowance.entitlement(2026), 26.0);
+    }
+
+    #[test]
+    fn days_left_carry_over_into_the_next_years() {
+        let allowance = VacationAllowance {
+            first_year: Some(2024),
+            carried_over_days: 2.0,
+            ..VacationAllowance::default()
+        };
+        let mut entries = vacation_days(2024, 20);
+        entries.extend(vacation_days(2025, 30));
+        let ledger =
+            |year| vacation_ledger(&allowance, year, &entries, &WorkCalendar::four_day_week());
+
+        assert_eq!(ledger(2023).carried_over, 0.0);
+        assert_eq!(ledger(2024).carried_over, 2.0);
+        assert_eq!(ledger(2025).carried_over, 8.0);
+        assert_eq!(ledger(2025).remaining(), 4.0);
+        assert_eq!(ledger(2026).carried_over, 4.0);
+    }
+
+    #[test]
+    fn carry_over_is_limited_but_a_deficit_is_not() {
+        let allowance = VacationAllowance {
+            first_year: Some(2024),
+            carried_over_days: 2.0,
+            carry_over_limit: Some(5.0),
+            ..VacationAllowance::default()
+        };
+        let mut entries = vacation_days(2024, 20);
+        entries.extend(vacation_days(2025, 35));
+        let ledger =
+            |year| vacation_ledger(&allowance, year, &entries, &WorkCalendar::four_day_week());
+
+        assert_eq!(ledger(2025).carried_over, 5.0);
+        assert_eq!(ledger(2026).carried_over, -4.0);
+    }
+}
@@ -0,0 +1,66 @@
+use super::calendar_state::CalendarDate;
+use super::friday_cycle::WorkingFridays;
+use super::holidays::HolidayCalendar;
+use super::user_settings::UserSettings;
+use super::work_schedule::WorkSchedule;
+use chrono::prelude::*;
+
+/// Everything that decides how many hours are expected on a date
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkCalendar {
+    pub schedule: WorkSchedule,
+    pub working_fridays: WorkingFridays,
+    pub holidays: HolidayCalendar,
+    /// Days off that are not public holidays, so they leave the statutory norm alone
+    pub days_off: HolidayCalendar,
+}
+
+impl WorkCalendar {
+    /// Work calendar of the user settings, with the Fridays overridden by hand
+    pub fn from_settings(
+        settings: &UserSettings,
+        friday_overrides: impl IntoIterator<Item = (CalendarDate, bool)>,
+    ) -> Self {
+        Self {
+            schedule: settings.work_schedule.clone(),
+            working_fridays: WorkingFridays::new(
+                settings.work_schedule.friday_cycle,
+                friday_overrides,
+            ),
+            holidays: settings.holiday_preset.calendar(),
+            days_off: HolidayCalendar {
+                holidays: settings.days_off.clone(),
+            },
+        }
+    }
+
+    /// Name of the holiday or day off falling on a date
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&str> {
+        self.holidays
+            .holiday_on(date)
+            .or_else(|| self.days_off.holiday_on(date))
+    }
+
+    /// Expected hours on a date: none on holidays, otherwise what the schedule asks for
+    pub fn expected_hours(&self, date: NaiveDate) -> f32 {
+        if self.holiday_on(date).is_some() {
+            return 0.0;
+        }
+        self.schedule.expected_hours(date, &self.working_fridays)
+    }
+}
+
+#[cfg(test)]
+impl WorkCalendar {
+    /// 8 hours Monday to Thursday, every Friday free, no holidays
+    pub(crate) fn four_day_week() -> Self {
+        let friday_cycle =
+            super::friday_cycle::FridayCycle::new(super::date(2025, 1, 3), 0).unwrap();
+        Self {
+            schedule: WorkSchedule::with_friday_cycle(friday_cycle),
+            working_fridays: WorkingFridays::new(friday_cycle, Vec::new()),
+            holidays: HolidayCalendar::default(),
+            days_off: HolidayCalendar::default(),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::friday_cycle::{FridayCycle, WorkingFridays};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Expected working time for each day of the week
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkSchedule {
+    /// Expected minutes from Monday to Sunday
+    pub daily_minutes: [u32; 7],
+    /// Which Fridays are worked; a free Friday expects no time regardless of `daily_minutes`
+    pub friday_cycle: FridayCycle,
+}
+
+impl WorkSchedule {
+    /// 8 hours Monday to Friday on the given Friday cycle, weekends free
+    pub fn with_friday_cycle(friday_cycle: FridayCycle) -> Self {
+        Self {
+            daily_minutes: [480, 480, 480, 480, 480, 0, 0],
+            friday_cycle,
+        }
+    }
+
+    /// Expected minutes for a weekday, before Friday cycle and overrides are applied
+    pub fn weekday_minutes(&self, weekday: Weekday) -> u32 {
+        self.daily_minutes[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Expected hours on a date, honouring the Friday cycle and its overrides
+    pub fn expected_hours(&self, date: NaiveDate, working_fridays: &WorkingFridays) -> f32 {
+        let weekday = date.weekday();
+        if weekday == Weekday::Fri && !working_fridays.is_working_friday(date) {
+            return 0.0;
+        }
+        self.weekday_minutes(weekday) as f32 / 60.0
+    }
+}
+
+impl Default for WorkSchedule {
+    fn default() -> Self {
+        Self::with_friday_cycle(FridayCycle::default())
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A break taken during the working day
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakInterval {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// Clock-in and clock-out times of a day, with the breaks taken in between
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkSpan {
+    pub start: NaiveTime,
+    /// Clock-out time, on the next day when it is not after `start`; a span from midnight to
+    /// midnight covers the whole day
+    pub end: NaiveTime,
+    #[serde(default)]
+    pub breaks: Vec<BreakInterval>,
+}
+
+impl WorkSpan {
+    /// Minutes from clock-in to clock-out
+    pub fn span_minutes(&self) -> u32 {
+        match self.offset(self.end) {
+            0 => MINUTES_PER_DAY,
+            minutes => minutes,
+        }
+    }
+
+    /// Minutes spent on breaks
+    pub fn break_minutes(&self) -> u32 {
+        self.breaks
+            .iter()
+            .map(|interval| {
+                self.offset(interval.end)
+                    .saturating_sub(self.offset(interval.start))
+            })
+            .sum()
+    }
+
+    /// Minutes worked: the span without the breaks
+    pub fn worked_minutes(&self) -> u32 {
+        self.span_minutes().saturating_sub(self.break_minutes())
+    }
+
+    /// Check that the span is not empty, and that the breaks fall within it and do not overlap
+    pub fn validate(&self) -> Result<(), &'static str> {
+        // Only a span from midnight to midnight is taken to mean a full day
+        if self.start == self.end && self.start != NaiveTime::MIN {
+            return Err("clock-out must differ from clock-in");
+        }
+        let mut breaks = self
+            .breaks
+            .iter()
+            .map(|interval| (self.offset(interval.start), self.offset(interval.end)))
+            .collect::<Vec<_>>();
+        breaks.sort_unstable();
+
+        let mut previous_end = 0;
+        for (start, end) in breaks {
+            if end <= start || end > self.span_minutes() {
+                return Err("every break must end after it starts, within the working hours");
+            }
+            if start < previous_end {
+                return Err("breaks must not overlap");
+            }
+            previous_end = end;
+        }
+        Ok(())
+    }
+
+    /// Format the span as `HH:MM–HH:MM`
+    pub fn format(&self) -> String {
+        format!(
+            "{}–{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+
+    /// Minutes from clock-in to a time, counting past midnight
+    fn offset(&self, time: NaiveTime) -> u32 {
+        let minutes = |time: NaiveTime| time.hour() * 60 + time.minute();
+        (minutes(time) + MINUTES_PER_DAY - minutes(self.start)) % MINUTES_PER_DAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn span(start: NaiveTime, end: NaiveTime, breaks: &[(NaiveTime, NaiveTime)]) -> WorkSpan {
+        WorkSpan {
+            start,
+            end,
+            breaks: breaks
+                .iter()
+                .map(|&(start, end)| BreakInterval { start, end })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn breaks_are_taken_off_the_span() {
+        let day = span(time(9, 0), time(17, 0), &[(time(12, 0), time(12, 30))]);
+
+        assert_eq!(day.validate(), Ok(()));
+        assert_eq!(day.span_minutes(), 480);
+        assert_eq!(day.break_minutes(), 30);
+        assert_eq!(day.worked_minutes(), 450);
+    }
+
+    #[test]
+    fn span_runs_past_midnight() {
+        let night = span(time(22, 0), time(6, 0), &[(time(23, 45), time(0, 15))]);
+
+        assert_eq!(night.validate(), Ok(()));
+        assert_eq!(night.span_minutes(), 480);
+        assert_eq!(night.worked_minutes(), 450);
+    }
+
+    #[test]
+    fn breaks_must_fit_the_span_without_overlapping() {
+        let outside = span(time(9, 0), time(17, 0), &[(time(16, 45), time(17, 15))]);
+        let overlapping = span(
+            time(9, 0),
+            time(17, 0),
+            &[(time(12, 0), time(12, 30)), (time(12, 15), time(12, 45))],
+        );
+        let reversed = span(time(9, 0), time(17, 0), &[(time(12, 30), time(12, 0))]);
+
+        assert!(outside.validate().is_err());
+        assert!(overlapping.validate().is_err());
+        assert!(reversed.validate().is_err());
+    }
+
+    #[test]
+    fn empty_span_is_rejected_unless_it_covers_the_whole_day() {
+        let empty = span(time(9, 0), time(9, 0), &[]);
+        let whole_day = span(time(0, 0), time(0, 0), &[]);
+
+        assert!(empty.validate().is_err());
+        assert_eq!(whole_day.validate(), Ok(()));
+        assert_eq!(whole_day.span_minutes(), 24 * 60);
+    }
+}
@@ -1,2 +1,8 @@
 pub mod home;
 pub mod calendars;
+pub mod day;
+pub mod export;
+pub mod invoices;
+pub mod month;
+pub mod not_found;
+pub mod settings;
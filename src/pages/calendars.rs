@@ -1,32 +1,30 @@
+use crate::api::{
+    get_settings, load_flexitime_balance, load_friday_overrides, load_month, load_projects,
+    DeleteDay, SaveDay, SetWorkingFriday,
+};
+use crate::components::{meeting_import::MeetingImport, time_input_modal::TimeInputModal};
+use crate::models::{
+    balance::{format_delta, month_balance},
+    billing::{billable_lines, format_amount},
+    calendar_state::{CalendarDate, CALENDAR_YEARS},
+    day_data::{DayData, EntryKind},
+    labour_code::{statutory_norm_hours, NormMode},
+    project::project_totals,
+    work_calendar::WorkCalendar,
+};
 use chrono::prelude::*;
 use leptos::prelude::*;
+use leptos_router::{components::A, hooks::use_navigate};
 use std::collections::HashMap;
 
-
-// Types module
-mod types {
-    use std::collections::HashMap;
-    #[derive(Clone, Debug)]
-    pub struct DayData {
-        pub hours: f32,
-    }
-
-    #[derive(Clone, Debug)]
-    pub struct CalendarState {
-        pub hours_data: HashMap<(i32, u32, u32), DayData>,
-        pub selected_date: Option<(i32, u32, u32)>,
-        pub show_modal: bool,
-    }
-}
-
 // Date utilities module
-mod date_utils {
+pub mod date_utils {
     use chrono::prelude::*;
 
     pub fn get_days_in_month(year: i32, month: u32) -> u32 {
         match month {
             2 => {
-                if year % 4 == 0 {
+                if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
                     29
                 } else {
                     28
@@ -59,108 +57,322 @@ mod date_utils {
         }
     }
 
-    pub fn is_working_friday(day: u32, first_friday_date: i32) -> bool {
-        let friday_number = (day - first_friday_date as u32) / 14 + 1;
-        friday_number % 2 != 0
+    pub fn previous_month(year: i32, month: u32) -> (i32, u32) {
+        if month == 1 {
+            (year - 1, 12)
+        } else {
+            (year, month - 1)
+        }
+    }
+
+    pub fn next_month(year: i32, month: u32) -> (i32, u32) {
+        if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        }
     }
 }
 
 use date_utils::*;
-use types::*;
 
-// Modal component
-#[component]
-fn HoursModal(
-    selected_date: ReadSignal<Option<(i32, u32, u32)>>,
-    hours_data: ReadSignal<HashMap<(i32, u32, u32), DayData>>,
-    show_modal: ReadSignal<bool>,
-    set_show_modal: WriteSignal<bool>,
-    on_save: fn(f32) -> (),
-) -> impl IntoView {
-    move || {
-        show_modal.get().then(|| {
-            let (year, month, day) = selected_date.get().unwrap();
-            let current_hours = hours_data
-                .get()
-                .get(&(year, month, day))
-                .map(|data| data.hours)
-                .unwrap_or(0.0);
-            let (input_hours, set_input_hours) = signal(current_hours);
-            
-            view! {
-                <div class="modal">
-                    <div class="modal-content">
-                        <h3>
-                            {format!("Enter hours for {} {}, {}", get_month_name(month), day, year)}
-                        </h3>
-                        <input
-                            type="number"
-                            step="0.5"
-                            min="0"
-                            max="24"
-                            prop:value=input_hours
-                            on:input=move |ev| {
-                                if let Ok(hours) = event_target_value(&ev).parse::<f32>() {
-                                    set_input_hours.set(hours);
-                                }
-                            }
-                        />
-                        <div class="modal-buttons">
-                            <button on:click=move |_| {
-                                set_show_modal.set(false)
-                            }>"Cancel"</button>
-                            <button on:click=move |_| on_save(input_hours.get())>"Save"</button>
-                        </div>
-                    </div>
-                </div>
-            }
-        })
-    }
+/// Path of the calendar page for a month
+pub fn month_path(year: i32, month: u32) -> String {
+    format!("/calendar/{year}/{month:02}")
 }
 
 #[component]
-pub fn Calendar() -> impl IntoView {
-    let today: DateTime<Local> = Local::now();
-    let current_month = today.month();
-    let current_year = today.year();
+pub fn Calendar(
+    /// Month shown, as (year, month)
+    #[prop(into)]
+    month: Signal<(i32, u32)>,
+    /// Date whose hours input is open when the calendar first renders
+    #[prop(optional)]
+    selected: Option<CalendarDate>,
+    /// Called after logged hours were saved or cleared
+    #[prop(optional, into)]
+    on_change: Option<Callback<()>>,
+    /// Reload the logged hours whenever this changes, after they were logged elsewhere
+    #[prop(optional, into)]
+    reload: Signal<usize>,
+) -> impl IntoView {
+    let today = Local::now().date_naive();
+    let view_month = month;
+    let navigate = use_navigate();
 
     // State management
-    let (hours_data, set_hours_data) = signal(HashMap::<(i32, u32, u32), DayData>::new());
-    let (selected_date, set_selected_date) = signal::<Option<(i32, u32, u32)>>(None);
-    let (show_modal, set_show_modal) = signal(false);
+    let (selected_date, set_selected_date) = signal::<Option<CalendarDate>>(selected);
+    let (show_modal, set_show_modal) = signal(selected.is_some());
+    // Busy time read from a calendar file, offered in the hours input of each day
+    let suggestions = RwSignal::new(HashMap::<CalendarDate, DayData>::new());
+
+    // Server state: logged hours are reloaded whenever the month changes or a save or delete completes
+    let save_day = ServerAction::<SaveDay>::new();
+    let delete_day = ServerAction::<DeleteDay>::new();
+    let month_data = Resource::new(
+        move || {
+            (
+                view_month.get(),
+                save_day.version().get(),
+                delete_day.version().get(),
+                reload.get(),
+            )
+        },
+        |((year, month), _, _, _)| load_month(year, month),
+    );
+    if let Some(on_change) = on_change {
+        Effect::watch(
+            move || (save_day.version().get(), delete_day.version().get()),
+            move |_, _, _| on_change.run(()),
+            false,
+        );
+    }
+    let set_working_friday = ServerAction::<SetWorkingFriday>::new();
+    let settings = Resource::new(|| (), |_| get_settings());
+    let projects = Resource::new(|| (), |_| load_projects());
+    let friday_overrides = Resource::new(
+        move || (view_month.get(), set_working_friday.version().get()),
+        |((year, month), _)| load_friday_overrides(year, month),
+    );
+    let flexitime = Resource::new(
+        move || {
+            (
+                view_month.get(),
+                save_day.version().get(),
+                delete_day.version().get(),
+                set_working_friday.version().get(),
+                reload.get(),
+            )
+        },
+        |((year, month), _, _, _, _)| load_flexitime_balance(year, month),
+    );
+    let user_settings = move || settings.get().and_then(Result::ok).unwrap_or_default();
+    let work_calendar = move || {
+        WorkCalendar::from_settings(
+            &user_settings(),
+            friday_overrides
+                .get()
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+        )
+    };
+    let project_list = move || projects.get().and_then(Result::ok).unwrap_or_default();
+    let hours_data = move || {
+        month_data
+            .get()
+            .and_then(Result::ok)
+            .map(|entries| entries.into_iter().collect::<HashMap<_, _>>())
+            .unwrap_or_default()
+    };
+
+    // The hours input stays open until the server accepted the change, showing why it did not
+    let server_error = RwSignal::new(None::<String>);
+    let settle = move |result: Option<Result<(), ServerFnError>>| match result {
+        Some(Ok(())) => set_show_modal.set(false),
+        Some(Err(e)) => server_error.set(Some(e.to_string())),
+        None => {}
+    };
+    Effect::watch(
+        move || save_day.version().get(),
+        move |_, _, _| settle(save_day.value().get_untracked()),
+        false,
+    );
+    Effect::watch(
+        move || delete_day.version().get(),
+        move |_, _, _| settle(delete_day.value().get_untracked()),
+        false,
+    );
+    // Rebuilt only when another day is opened, so that reloading after a refused save keeps what
+    // was typed
+    let open_date = Memo::new(move |_| {
+        show_modal
+            .get()
+            .then(|| selected_date.get())
+            .flatten()
+            .filter(|_| month_data.get().is_some())
+    });
 
     // Handler for day clicks
     let handle_day_click = move |year: i32, month: u32, day: u32| {
         set_selected_date.set(Some((year, month, day)));
+        server_error.set(None);
         set_show_modal.set(true);
     };
 
+    // Handler for jumping to a month picked in the month/year inputs
+    let show_month = move |year: i32, month: u32| {
+        if CALENDAR_YEARS.contains(&year) {
+            navigate(&month_path(year, month), Default::default());
+        }
+    };
+    let show_month_of_year = show_month.clone();
+
     // Handler for saving hours
-    let save_hours = move |hours: f32| {
+    let save_time = move |data: DayData| {
         if let Some((year, month, day)) = selected_date.get() {
-            set_hours_data.update(|data| {
-                data.insert((year, month, day), DayData { hours });
+            server_error.set(None);
+            save_day.dispatch(SaveDay {
+                year,
+                month,
+                day,
+                data,
             });
         }
-        set_show_modal.set(false);
     };
 
-    // Get the first day of the month
-    let first_day = create_datetime(current_year, current_month, 1);
-    let days_in_month = get_days_in_month(current_year, current_month);
+    // Handler for the Working Friday checkbox
+    let toggle_working_friday = move |working: bool| {
+        if let Some((year, month, day)) = selected_date.get() {
+            set_working_friday.dispatch(SetWorkingFriday {
+                year,
+                month,
+                day,
+                working,
+            });
+        }
+    };
 
-    // Get the weekday of the first day (0 = Monday, 6 = Sunday)
-    let first_weekday = first_day.weekday().num_days_from_monday();
+    // Handler for clearing hours
+    let clear_hours = move || {
+        if let Some((year, month, day)) = selected_date.get() {
+            server_error.set(None);
+            delete_day.dispatch(DeleteDay { year, month, day });
+        }
+    };
 
-    let month_name = get_month_name(current_month);
+    // Handler for the busy time read from a calendar file
+    let suggest_hours = move |days: Vec<(CalendarDate, DayData)>| {
+        suggestions.set(days.into_iter().collect());
+    };
 
     view! {
         <div class="calendar">
-            <h2>{month_name} " " {current_year}</h2>
+            <div class="calendar-header">
+                <A
+                    href=move || {
+                        let (year, month) = view_month.get();
+                        let (year, month) = previous_month(year, month);
+                        month_path(year, month)
+                    }
+                    attr:class="month-nav"
+                >
+                    "‹"
+                </A>
+                <h2>
+                    {move || {
+                        let (year, month) = view_month.get();
+                        format!("{} {}", get_month_name(month), year)
+                    }}
+                </h2>
+                <A
+                    href=move || {
+                        let (year, month) = view_month.get();
+                        let (year, month) = next_month(year, month);
+                        month_path(year, month)
+                    }
+                    attr:class="month-nav"
+                >
+                    "›"
+                </A>
+            </div>
+            <Transition fallback=|| ()>
+                {move || {
+                    flexitime
+                        .get()
+                        .and_then(Result::ok)
+                        .map(|balance| {
+                            view! {
+                                <div class="flexitime-balance">
+                                    "Flexitime balance: "
+                                    <strong class=delta_class(balance.closing)>
+                                        {format_delta(balance.closing)}
+                                    </strong>
+                                    {format!(
+                                        " (carried over {})",
+                                        format_delta(balance.carried_in),
+                                    )}
+                                </div>
+                            }
+                        })
+                }}
+            </Transition>
+            <div class="month-picker">
+                <A href=month_path(today.year(), today.month()) attr:class="today-link">
+                    "Today"
+                </A>
+                <select
+                    prop:value=move || view_month.get().1.to_string()
+                    on:change=move |ev| {
+                        if let Ok(month) = event_target_value(&ev).parse::<u32>() {
+                            show_month(view_month.get_untracked().0, month);
+                        }
+                    }
+                >
+                    {(1..=12)
+                        .map(|month| {
+                            view! {
+                                <option value=month.to_string()>{get_month_name(month)}</option>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+                <input
+                    type="number"
+                    min=CALENDAR_YEARS.start().to_string()
+                    max=CALENDAR_YEARS.end().to_string()
+                    prop:value=move || view_month.get().0
+                    on:change=move |ev| {
+                        if let Ok(year) = event_target_value(&ev).parse::<i32>() {
+                            show_month_of_year(year, view_month.get_untracked().1);
+                        }
+                    }
+                />
+            </div>
             <div class="working-hours">
-                {"Working hours this month: "}
-                {calculate_working_hours(current_year, current_month)}
+                <Transition fallback=|| ()>
+                    {move || {
+                        let (year, month) = view_month.get();
+                        match user_settings().norm_mode {
+                            NormMode::Schedule => {
+                                format!(
+                                    "Working hours this month: {}",
+                                    calculate_working_hours(year, month, &work_calendar()),
+                                )
+                            }
+                            // The balances below count the schedule, this is for payroll reference
+                            NormMode::LabourCode => {
+                                format!(
+                                    "Labour Code norm this month: {} (for reference, the balance follows your schedule)",
+                                    calculate_statutory_hours(year, month, &work_calendar().holidays),
+                                )
+                            }
+                        }
+                    }}
+                </Transition>
             </div>
+            <Transition fallback=|| ()>
+                {move || {
+                    let settings = user_settings();
+                    let period = settings.settlement_period;
+                    (settings.norm_mode == NormMode::LabourCode && period.months() > 1)
+                        .then(|| {
+                            let (year, month) = view_month.get();
+                            let (start, end) = period.bounds(year, month);
+                            let norm = statutory_norm_hours(start, end, &work_calendar().holidays);
+                            view! {
+                                <div class="working-hours">
+                                    {format!(
+                                        "Norm for the settlement period {} – {} {}: {}",
+                                        get_month_name(start.month()),
+                                        get_month_name(end.month()),
+                                        end.year(),
+                                        norm,
+                                    )}
+                                </div>
+                            }
+                        })
+                }}
+            </Transition>
             <div class="calendar-grid">
                 <div class="weekday">"Mon"</div>
                 <div class="weekday">"Tue"</div>
@@ -170,115 +382,278 @@ pub fn Calendar() -> impl IntoView {
                 <div class="weekday">"Sat"</div>
                 <div class="weekday">"Sun"</div>
 
-                // Empty cells for days before the first of the month
-                {(0..first_weekday)
-                    .map(|_| view! { <div class="day empty"></div> })
-                    .collect::<Vec<_>>()}
-
-                // Days of the month
-                {(1..=days_in_month)
-                    .map(move |day| {
-                        let is_today = day == today.day();
-                        let day_data = hours_data
-                            .get()
-                            .get(&(current_year, current_month, day))
-                            .cloned();
-                        let current_date = create_datetime(current_year, current_month, day);
-                        let weekday = current_date.weekday();
-                        let is_weekend = matches!(weekday, Weekday::Sat | Weekday::Sun);
-                        let is_working_friday = if weekday == Weekday::Fri {
-                            let first_friday_date = (7
-                                - (first_day.weekday().num_days_from_monday() as i32 - 4))
-                                .rem_euclid(7) + 1;
-                            let friday_number = (day - first_friday_date as u32) / 14 + 1;
-                            friday_number % 2 != 0
-                        } else {
-                            false
-                        };
-                        let day_class = match (is_today, day_data.is_some(), is_weekend, weekday) {
-                            (true, true, _, _) => "day today has-hours",
-                            (true, false, _, _) => "day today",
-                            (_, true, true, _) => "day has-hours weekend",
-                            (_, false, true, _) => "day weekend",
-                            (_, true, false, Weekday::Fri) if !is_working_friday => {
-                                "day has-hours non-working-friday"
-                            }
-                            (_, false, false, Weekday::Fri) if !is_working_friday => {
-                                "day non-working-friday"
-                            }
-                            (_, true, false, _) => "day has-hours workday",
-                            (_, false, false, _) => "day workday",
-                        };
-                        let year = current_year;
-                        let month = current_month;
-                        view! {
-                            // For Fridays, check if it's a working Friday
-
-                            <div
-                                class=day_class
-                                on:click=move |_| handle_day_click(year, month, day)
-                            >
-                                <span class="day-number">{day}</span>
-                                {day_data
-                                    .map(|data| {
-                                        view! {
-                                            <span class="hours-label">
-                                                {format!("{:.1}h", data.hours)}
-                                            </span>
-                                        }
-                                    })}
-                            </div>
-                        }
-                    })
-                    .collect::<Vec<_>>()}
+                <Transition fallback=|| ()>
+                    {move || {
+                        let (current_year, current_month) = view_month.get();
+                        let hours_data = hours_data();
+                        let work_calendar = work_calendar();
+                        let balance = month_balance(
+                            current_year,
+                            current_month,
+                            &hours_data,
+                            &work_calendar,
+                            today,
+                        );
+
+                        // Get the first day of the month
+                        let first_day = create_datetime(current_year, current_month, 1);
+                        let days_in_month = get_days_in_month(current_year, current_month);
+
+                        // Get the weekday of the first day (0 = Monday, 6 = Sunday)
+                        let first_weekday = first_day.weekday().num_days_from_monday();
+
+                        // Empty cells for days before the first of the month
+                        let empty_days = (0..first_weekday)
+                            .map(|_| view! { <div class="day empty"></div> })
+                            .collect::<Vec<_>>();
+
+                        // Days of the month
+                        let days = (1..=days_in_month)
+                            .map(move |day| {
+                                let is_today = (current_year, current_month, day)
+                                    == (today.year(), today.month(), today.day());
+                                let day_data = hours_data
+                                    .get(&(current_year, current_month, day))
+                                    .cloned();
+                                let current_date = create_datetime(current_year, current_month, day);
+                                let weekday = current_date.weekday();
+                                let holiday = work_calendar
+                                    .holiday_on(current_date.date_naive())
+                                    .map(str::to_string);
+                                let is_workday =
+                                    work_calendar.expected_hours(current_date.date_naive()) > 0.0;
+                                // Deltas are shown for days up to today that expect or log time
+                                let delta = balance
+                                    .days_to_date()
+                                    .find(|balance| balance.date == current_date.date_naive())
+                                    .filter(|balance| {
+                                        balance.logged_hours > 0.0 || balance.expected_hours > 0.0
+                                    })
+                                    .map(|balance| balance.delta());
+                                let suggested = suggestions
+                                    .with(|suggestions| {
+                                        suggestions.get(&(current_year, current_month, day)).cloned()
+                                    })
+                                    .filter(|_| day_data.is_none());
+                                let kind = day_data.as_ref().map(|data| data.kind());
+                                let day_class = match (is_today, day_data.is_some(), is_workday, weekday) {
+                                    _ if kind == Some(EntryKind::Vacation) => "day has-hours vacation",
+                                    _ if kind == Some(EntryKind::HalfDayVacation) => {
+                                        "day has-hours vacation"
+                                    }
+                                    _ if kind == Some(EntryKind::SickLeave) => "day has-hours sick-leave",
+                                    _ if kind == Some(EntryKind::PublicHoliday) => "day has-hours holiday",
+                                    _ if kind == Some(EntryKind::UnpaidLeave) => "day has-hours unpaid-leave",
+                                    _ if kind == Some(EntryKind::BusinessTrip) => {
+                                        "day has-hours business-trip"
+                                    }
+                                    (true, true, _, _) => "day today has-hours",
+                                    (true, false, _, _) => "day today",
+                                    (_, true, _, _) if holiday.is_some() => "day has-hours holiday",
+                                    (_, false, _, _) if holiday.is_some() => "day holiday",
+                                    (_, true, true, _) => "day has-hours workday",
+                                    (_, false, true, _) => "day workday",
+                                    (_, true, false, Weekday::Sat | Weekday::Sun) => {
+                                        "day has-hours weekend"
+                                    }
+                                    (_, false, false, Weekday::Sat | Weekday::Sun) => "day weekend",
+                                    (_, true, false, Weekday::Fri) => "day has-hours non-working-friday",
+                                    (_, false, false, Weekday::Fri) => "day non-working-friday",
+                                    (_, true, false, _) => "day has-hours day-off",
+                                    (_, false, false, _) => "day day-off",
+                                };
+                                let year = current_year;
+                                let month = current_month;
+                                view! {
+                                    // For Fridays, check if it's a working Friday
+
+                                    <div
+                                        class=day_class
+                                        on:click=move |_| handle_day_click(year, month, day)
+                                    >
+                                        <span class="day-number">{day}</span>
+                                        {holiday
+                                            .map(|name| {
+                                                view! { <span class="holiday-name">{name}</span> }
+                                            })}
+                                        {day_data
+                                            .map(|data| {
+                                                let label = match data.kind() {
+                                                    EntryKind::Work => format!("{:.1}h", data.to_hours()),
+                                                    kind if kind.is_absence() => kind.label().to_string(),
+                                                    kind => format!("{} {:.1}h", kind.label(), data.to_hours()),
+                                                };
+                                                let spans = data
+                                                    .sessions()
+                                                    .iter()
+                                                    .filter_map(|session| session.span.as_ref())
+                                                    .map(|span| {
+                                                        view! { <span class="span-label">{span.format()}</span> }
+                                                    })
+                                                    .collect::<Vec<_>>();
+                                                view! {
+                                                    <span class="hours-label">{label}</span>
+                                                    {spans}
+                                                }
+                                            })}
+                                        {suggested
+                                            .map(|data| {
+                                                view! {
+                                                    <span class="suggested-label">
+                                                        {format!("≈ {}", data.format())}
+                                                    </span>
+                                                }
+                                            })}
+                                        {delta
+                                            .map(|delta| {
+                                                view! {
+                                                    <span class=delta_class(delta)>
+                                                        {format_delta(delta)}
+                                                    </span>
+                                                }
+                                            })}
+                                    </div>
+                                }
+                            })
+                            .collect::<Vec<_>>();
+
+                        (empty_days, days)
+                    }}
+                </Transition>
             </div>
+            <Transition fallback=|| ()>
+                {move || {
+                    let (year, month) = view_month.get();
+                    let balance = month_balance(year, month, &hours_data(), &work_calendar(), today);
+                    let difference = balance.difference();
+                    view! {
+                        <div class="balance-summary">
+                            <div>
+                                <span>"Logged to date"</span>
+                                <strong>{format!("{:.1}h", balance.logged_to_date())}</strong>
+                            </div>
+                            <div>
+                                <span>"Expected to date"</span>
+                                <strong>{format!("{:.1}h", balance.expected_to_date)}</strong>
+                            </div>
+                            <div>
+                                <span>"Expected this month"</span>
+                                <strong>{format!("{:.1}h", balance.expected_month)}</strong>
+                            </div>
+                            <div>
+                                <span>{if difference < 0.0 { "Undertime" } else { "Overtime" }}</span>
+                                <strong class=delta_class(difference)>
+                                    {format_delta(difference)}
+                                </strong>
+                            </div>
+                            {balance
+                                .kind_counts()
+                                .into_iter()
+                                .map(|(kind, count)| {
+                                    view! {
+                                        <div>
+                                            <span>{kind.label()}</span>
+                                            <strong>
+                                                {if count == 1 {
+                                                    "1 day".to_string()
+                                                } else {
+                                                    format!("{count} days")
+                                                }}
+                                            </strong>
+                                        </div>
+                                    }
+                                })
+                                .collect::<Vec<_>>()}
+                        </div>
+                    }
+                }}
+            </Transition>
+            <Transition fallback=|| ()>
+                {move || {
+                    let projects = project_list();
+                    let currency = user_settings().billing.currency;
+                    let entries = month_data.get().and_then(Result::ok).unwrap_or_default();
+                    let totals = project_totals(entries.iter().map(|(_, data)| data));
+                    (!totals.is_empty())
+                        .then(|| {
+                            view! {
+                                <ul class="project-totals">
+                                    {totals
+                                        .into_iter()
+                                        .map(|(id, minutes)| {
+                                            let project = id
+                                                .and_then(|id| projects.iter().find(|project| project.id == id));
+                                            let name = match (id, project) {
+                                                (None, _) => "No project".to_string(),
+                                                (Some(_), Some(project)) => project.name.clone(),
+                                                (Some(id), None) => format!("Project #{id}"),
+                                            };
+                                            let colour = project
+                                                .map(|project| project.colour.clone())
+                                                .unwrap_or_default();
+                                            // Only time logged while the project had a rate is billable
+                                            let billable = project
+                                                .filter(|project| !project.rates.is_empty())
+                                                .map(|project| {
+                                                    let cents = billable_lines(project, &entries)
+                                                        .iter()
+                                                        .map(|line| line.amount_cents)
+                                                        .sum::<u64>();
+                                                    view! {
+                                                        <span class="billable">{format_amount(cents, &currency)}</span>
+                                                    }
+                                                });
+                                            view! {
+                                                <li>
+                                                    <span
+                                                        class="project-swatch"
+                                                        style:background-color=colour
+                                                    ></span>
+                                                    <span>{name}</span>
+                                                    <strong>
+                                                        {format!("{}:{:02}", minutes / 60, minutes % 60)}
+                                                    </strong>
+                                                    {billable}
+                                                </li>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()}
+                                </ul>
+                            }
+                        })
+                }}
+            </Transition>
+            <MeetingImport month=view_month on_suggest=Callback::new(suggest_hours)/>
 
             // Hours Input Modal
             {move || {
-                show_modal
+                open_date
                     .get()
-                    .then(|| {
-                        let (year, month, day) = selected_date.get().unwrap();
-                        let current_hours = hours_data
-                            .get()
-                            .get(&(year, month, day))
-                            .map(|data| data.hours)
-                            .unwrap_or(0.0);
-                        let (input_hours, set_input_hours) = signal(current_hours);
-                        view! {
-                            <div class="modal">
-                                <div class="modal-content">
-                                    <h3>
-                                        {format!(
-                                            "Enter hours for {} {}, {}",
-                                            get_month_name(month),
-                                            day,
-                                            year,
-                                        )}
-                                    </h3>
-                                    <input
-                                        type="number"
-                                        step="0.5"
-                                        min="0"
-                                        max="24"
-                                        prop:value=input_hours
-                                        on:input=move |ev| {
-                                            if let Ok(hours) = event_target_value(&ev).parse::<f32>() {
-                                                set_input_hours.set(hours);
-                                            }
-                                        }
-                                    />
-                                    <div class="modal-buttons">
-                                        <button on:click=move |_| {
-                                            set_show_modal.set(false)
-                                        }>"Cancel"</button>
-                                        <button on:click=move |_| save_hours(
-                                            input_hours.get(),
-                                        )>"Save"</button>
-                                    </div>
-                                </div>
-                            </div>
-                        }
+                    .map(|date| {
+                        untrack(|| {
+                            let (year, month, day) = date;
+                            let current_data = hours_data().get(&date).cloned().unwrap_or_default();
+                            let suggestion =
+                                suggestions.with(|suggestions| suggestions.get(&date).cloned());
+                            let is_working_friday = NaiveDate::from_ymd_opt(year, month, day)
+                                .is_some_and(|date| {
+                                    work_calendar().working_fridays.is_working_friday(date)
+                                });
+                            view! {
+                                <TimeInputModal
+                                    selected_date=date
+                                    current_data=current_data
+                                    is_working_friday=is_working_friday
+                                    projects=project_list()
+                                    suggestion=suggestion
+                                    on_save=Callback::new(save_time)
+                                    on_toggle_working_friday=Callback::new(toggle_working_friday)
+                                    on_clear=clear_hours
+                                    on_close=move || set_show_modal.set(false)
+                                    server_error=server_error
+                                />
+                            }
+                        })
                     })
             }}
         </div>
@@ -288,34 +663,38 @@ pub fn Calendar() -> impl IntoView {
 // Working hours calculator module
 mod working_hours {
     use super::*;
+    use crate::models::holidays::HolidayCalendar;
 
-    pub fn calculate_working_hours(year: i32, month: u32) -> f32 {
+    pub fn calculate_working_hours(year: i32, month: u32, work_calendar: &WorkCalendar) -> f32 {
         let mut total_hours = 0.0;
-        let first_day = create_datetime(year, month, 1);
         let days_in_month = get_days_in_month(year, month);
 
-        let first_friday_date =
-            (7 - (first_day.weekday().num_days_from_monday() as i32 - 4)).rem_euclid(7) + 1;
-
         for day in 1..=days_in_month {
             let current_day = create_datetime(year, month, day);
-            let weekday = current_day.weekday();
-
-            match weekday {
-                Weekday::Mon | Weekday::Tue | Weekday::Wed | Weekday::Thu => {
-                    total_hours += 8.0;
-                }
-                Weekday::Fri => {
-                    if is_working_friday(day, first_friday_date) {
-                        total_hours += 8.0;
-                    }
-                }
-                _ => {} // Weekend days
-            }
+            total_hours += work_calendar.expected_hours(current_day.date_naive());
         }
 
         total_hours
     }
+
+    pub fn calculate_statutory_hours(year: i32, month: u32, holidays: &HolidayCalendar) -> f32 {
+        let first_day = create_datetime(year, month, 1).date_naive();
+        let last_day = create_datetime(year, month, get_days_in_month(year, month)).date_naive();
+        statutory_norm_hours(first_day, last_day, holidays)
+    }
 }
 
-use working_hours::calculate_working_hours;
+use working_hours::{calculate_statutory_hours, calculate_working_hours};
+
+/// CSS class colouring an hour difference, left neutral when it is shown as `0.0h`
+fn delta_class(delta: f32) -> &'static str {
+    // Differences are shown rounded to a tenth of an hour
+    const SHOWN_AS_ZERO: f32 = 0.05;
+    if delta >= SHOWN_AS_ZERO {
+        "delta overtime"
+    } else if delta <= -SHOWN_AS_ZERO {
+        "delta undertime"
+    } else {
+        "delta"
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::prelude::*;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+use crate::models::calendar_state::CALENDAR_YEARS;
+use crate::pages::calendars::Calendar;
+use crate::pages::not_found::NotFound;
+
+/// Parse the `:date` route segment, written as `YYYY-MM-DD`
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .filter(|date| CALENDAR_YEARS.contains(&date.year()))
+}
+
+#[component]
+pub fn DayPage() -> impl IntoView {
+    let params = use_params_map();
+    let date = Memo::new(move |_| params.with(|params| parse_date(&params.get("date")?)));
+
+    move || match date.get() {
+        Some(date) => view! {
+            <h1>"Hours Counter"</h1>
+            <Calendar
+                month=Signal::derive(move || (date.year(), date.month()))
+                selected=(date.year(), date.month(), date.day())
+            />
+        }
+        .into_any(),
+        None => view! { <NotFound/> }.into_any(),
+    }
+}
@@ -0,0 +1,466 @@
+use chrono::{prelude::*, Months};
+use leptos::prelude::*;
+
+use crate::api::{preview_import, ImportCsv};
+use crate::components::file_input::read_chosen_file;
+use crate::models::{
+    day_data::DayData,
+    export::{DecimalSeparator, Delimiter, ExportOptions},
+    import::{ColumnMapping, ConflictMode, ImportOptions},
+};
+
+#[component]
+pub fn ExportPage() -> impl IntoView {
+    let today = Local::now().date_naive();
+    let month_start = today.with_day(1).unwrap_or(today);
+    let month_end = month_start
+        .checked_add_months(Months::new(1))
+        .and_then(|next_month| next_month.pred_opt())
+        .unwrap_or(today);
+    let options = RwSignal::new(ExportOptions {
+        from: month_start,
+        to: month_end,
+        delimiter: Delimiter::default(),
+        decimal: DecimalSeparator::default(),
+    });
+    let valid_range = move || options.with(|options| options.from <= options.to);
+    let timesheet_month = RwSignal::new((today.year(), today.month()));
+
+    view! {
+        <h1>"Import and export"</h1>
+        <div class="settings">
+            <h2>"Logged days"</h2>
+            <div class="settings-grid">
+                <label>"From"</label>
+                <input
+                    type="date"
+                    prop:value=move || options.get().from.format("%Y-%m-%d").to_string()
+                    on:change=move |ev| {
+                        if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") {
+                            options.update(|options| options.from = date);
+                        }
+                    }
+                />
+                <label>"To"</label>
+                <input
+                    type="date"
+                    prop:value=move || options.get().to.format("%Y-%m-%d").to_string()
+                    on:change=move |ev| {
+                        if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") {
+                            options.update(|options| options.to = date);
+                        }
+                    }
+                />
+                <label>"CSV delimiter"</label>
+                <select
+                    prop:value=move || options.get().delimiter.key()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(delimiter) = Delimiter::ALL
+                            .into_iter()
+                            .find(|delimiter| delimiter.key() == value)
+                        {
+                            options.update(|options| options.delimiter = delimiter);
+                        }
+                    }
+                >
+                    {Delimiter::ALL
+                        .into_iter()
+                        .map(|delimiter| {
+                            view! { <option value=delimiter.key()>{delimiter.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+                <label>"Decimal separator"</label>
+                <select
+                    prop:value=move || options.get().decimal.key()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(decimal) = DecimalSeparator::ALL
+                            .into_iter()
+                            .find(|decimal| decimal.key() == value)
+                        {
+                            options.update(|options| options.decimal = decimal);
+                        }
+                    }
+                >
+                    {DecimalSeparator::ALL
+                        .into_iter()
+                        .map(|decimal| {
+                            view! { <option value=decimal.key()>{decimal.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            </div>
+            {move || {
+                (!valid_range())
+                    .then(|| view! { <p class="error">"The range ends before it starts."</p> })
+            }}
+            <div class="modal-buttons">
+                <a
+                    class="button"
+                    class:disabled=move || !valid_range()
+                    href=move || format!("/export/csv?{}", options.get().query())
+                    rel="external"
+                    download
+                >
+                    "Download CSV"
+                </a>
+                <a
+                    class="button"
+                    class:disabled=move || !valid_range()
+                    href=move || format!("/export/ics?{}", options.get().query())
+                    rel="external"
+                    download
+                >
+                    "Download iCalendar"
+                </a>
+            </div>
+        </div>
+        <div class="settings">
+            <h2>"Monthly timesheet"</h2>
+            <div class="settings-grid">
+                <label>"Month"</label>
+                <input
+                    type="month"
+                    prop:value=move || {
+                        let (year, month) = timesheet_month.get();
+                        format!("{year:04}-{month:02}")
+                    }
+                    on:change=move |ev| {
+                        let value = format!("{}-01", event_target_value(&ev));
+                        if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                            timesheet_month.set((date.year(), date.month()));
+                        }
+                    }
+                />
+            </div>
+            <div class="modal-buttons">
+                <a
+                    class="button"
+                    href=move || {
+                        let (year, month) = timesheet_month.get();
+                        format!("/export/xlsx?year={year}&month={month}")
+                    }
+                    rel="external"
+                    download
+                >
+                    "Download XLSX"
+                </a>
+                <a
+                    class="button"
+                    href=move || {
+                        let (year, month) = timesheet_month.get();
+                        format!("/timesheet/{year:04}-{month:02}.html")
+                    }
+                    rel="external"
+                    target="_blank"
+                >
+                    "Print"
+                </a>
+                <a
+                    class="button"
+                    href=move || {
+                        let (year, month) = timesheet_month.get();
+                        format!("/timesheet/{year:04}-{month:02}.pdf")
+                    }
+                    rel="external"
+                    target="_blank"
+                >
+                    "PDF"
+                </a>
+            </div>
+        </div>
+        <CsvImport/>
+    }
+}
+
+/// Upload of a CSV file, previewed with its column mapping before anything is stored
+#[component]
+fn CsvImport() -> impl IntoView {
+    let import_csv = ServerAction::<ImportCsv>::new();
+    let content = RwSignal::new(String::new());
+    let options = RwSignal::new(ImportOptions::default());
+    let conflict = RwSignal::new(ConflictMode::default());
+    let preview = Resource::new(
+        move || (content.get(), options.get(), import_csv.version().get()),
+        |(content, options, _)| async move {
+            if content.is_empty() {
+                None
+            } else {
+                Some(preview_import(content, options).await)
+            }
+        },
+    );
+    // The first preview of a file guesses the columns, which are then kept while editing them
+    Effect::new(move |_| {
+        if let Some(Some(Ok(preview))) = preview.get() {
+            if options.with_untracked(|options| options.mapping.is_none()) {
+                options.update(|options| options.mapping = Some(preview.mapping));
+            }
+        }
+    });
+
+    let load_file = move |ev: leptos::ev::Event| {
+        read_chosen_file(&ev, move |text| {
+            options.update(|options| options.mapping = None);
+            content.set(text);
+        });
+    };
+    let import = move |_| {
+        import_csv.dispatch(ImportCsv {
+            content: content.get(),
+            options: options.get(),
+            conflict: conflict.get(),
+        });
+    };
+    let status = move || {
+        import_csv.value().get().map(|result| match result {
+            Ok(summary) => view! {
+                <p class="saved">
+                    {format!(
+                        "Imported: {} added, {} overwritten, {} merged, {} skipped, {} failed.",
+                        summary.added,
+                        summary.overwritten,
+                        summary.merged,
+                        summary.skipped,
+                        summary.failed,
+                    )}
+                </p>
+            }
+            .into_any(),
+            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+        })
+    };
+
+    view! {
+        <div class="settings">
+            <h2>"Import"</h2>
+            <div class="settings-grid">
+                <label>"CSV file, its first row naming the columns"</label>
+                <input type="file" accept=".csv,text/csv" on:change=load_file/>
+                <label>"CSV delimiter"</label>
+                <select
+                    prop:value=move || options.get().delimiter.key()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(delimiter) = Delimiter::ALL
+                            .into_iter()
+                            .find(|delimiter| delimiter.key() == value)
+                        {
+                            // Columns are guessed again, as the header row now splits differently
+                            options.update(|options| {
+                                options.delimiter = delimiter;
+                                options.mapping = None;
+                            });
+                        }
+                    }
+                >
+                    {Delimiter::ALL
+                        .into_iter()
+                        .map(|delimiter| {
+                            view! { <option value=delimiter.key()>{delimiter.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+                <label>"Decimal separator"</label>
+                <select
+                    prop:value=move || options.get().decimal.key()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(decimal) = DecimalSeparator::ALL
+                            .into_iter()
+                            .find(|decimal| decimal.key() == value)
+                        {
+                            options.update(|options| options.decimal = decimal);
+                        }
+                    }
+                >
+                    {DecimalSeparator::ALL
+                        .into_iter()
+                        .map(|decimal| {
+                            view! { <option value=decimal.key()>{decimal.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            </div>
+            <Transition fallback=|| view! { <p>"Reading file..."</p> }>
+                {move || {
+                    preview
+                        .get()
+                        .flatten()
+                        .map(|preview| match preview {
+                            Ok(preview) => {
+                                let rows = preview.rows;
+                                let errors = rows.iter().filter(|row| row.entry.is_err()).count();
+                                let conflicts = rows
+                                    .iter()
+                                    .filter(|row| row.entry.is_ok() && row.existing.is_some())
+                                    .count();
+                                let readable = rows.len() - errors;
+                                view! {
+                                    <h3>"Columns"</h3>
+                                    <div class="settings-grid">
+                                        <ColumnSelect
+                                            label="Date"
+                                            headers=preview.headers.clone()
+                                            options
+                                            column=|mapping| &mut mapping.date
+                                        />
+                                        <ColumnSelect
+                                            label="Decimal hours"
+                                            headers=preview.headers.clone()
+                                            options
+                                            column=|mapping| &mut mapping.decimal_hours
+                                        />
+                                        <ColumnSelect
+                                            label="Hours, unless decimal hours are mapped"
+                                            headers=preview.headers.clone()
+                                            options
+                                            column=|mapping| &mut mapping.hours
+                                        />
+                                        <ColumnSelect
+                                            label="Minutes, unless decimal hours are mapped"
+                                            headers=preview.headers.clone()
+                                            options
+                                            column=|mapping| &mut mapping.minutes
+                                        />
+                                        <ColumnSelect
+                                            label="Note"
+                                            headers=preview.headers
+                                            options
+                                            column=|mapping| &mut mapping.note
+                                        />
+                                    </div>
+                                    <h3>"Preview"</h3>
+                                    <p>
+                                        {format!(
+                                            "{readable} rows can be imported, {errors} cannot be read and {conflicts} fall on days already logged.",
+                                        )}
+                                    </p>
+                                    <table class="import-preview">
+                                        <tr>
+                                            <th>"Line"</th>
+                                            <th>"Date"</th>
+                                            <th>"Hours"</th>
+                                            <th>"Note"</th>
+                                            <th>"Already logged"</th>
+                                        </tr>
+                                        {rows
+                                            .into_iter()
+                                            .map(|row| {
+                                                let existing = row.existing.as_ref().map(DayData::format);
+                                                match row.entry {
+                                                    Ok(((year, month, day), data)) => {
+                                                        let note = data
+                                                            .sessions()
+                                                            .first()
+                                                            .map(|session| session.note.clone())
+                                                            .unwrap_or_default();
+                                                        let logged = existing.is_some();
+                                                        view! {
+                                                            <tr class:conflict=logged>
+                                                                <td>{row.line}</td>
+                                                                <td>{format!("{year:04}-{month:02}-{day:02}")}</td>
+                                                                <td>{data.format()}</td>
+                                                                <td>{note}</td>
+                                                                <td>{existing}</td>
+                                                            </tr>
+                                                        }
+                                                            .into_any()
+                                                    }
+                                                    Err(message) => {
+                                                        view! {
+                                                            <tr class="error">
+                                                                <td>{row.line}</td>
+                                                                <td colspan="4">{message}</td>
+                                                            </tr>
+                                                        }
+                                                            .into_any()
+                                                    }
+                                                }
+                                            })
+                                            .collect::<Vec<_>>()}
+                                    </table>
+                                    <div class="settings-grid">
+                                        <label>"When a day is already logged"</label>
+                                        <select
+                                            prop:value=move || format!("{:?}", conflict.get())
+                                            on:change=move |ev| {
+                                                let value = event_target_value(&ev);
+                                                if let Some(mode) = ConflictMode::ALL
+                                                    .into_iter()
+                                                    .find(|mode| format!("{mode:?}") == value)
+                                                {
+                                                    conflict.set(mode);
+                                                }
+                                            }
+                                        >
+                                            {ConflictMode::ALL
+                                                .into_iter()
+                                                .map(|mode| {
+                                                    view! { <option value=format!("{mode:?}")>{mode.label()}</option> }
+                                                })
+                                                .collect::<Vec<_>>()}
+                                        </select>
+                                    </div>
+                                    <div class="modal-buttons">
+                                        <button on:click=import disabled=readable == 0>
+                                            "Import"
+                                        </button>
+                                    </div>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+            {status}
+        </div>
+    }
+}
+
+/// Picker of the column holding one value of an imported row
+#[component]
+fn ColumnSelect(
+    label: &'static str,
+    headers: Vec<String>,
+    options: RwSignal<ImportOptions>,
+    column: fn(&mut ColumnMapping) -> &mut Option<usize>,
+) -> impl IntoView {
+    view! {
+        <label>{label}</label>
+        <select
+            prop:value=move || {
+                options
+                    .get()
+                    .mapping
+                    .and_then(|mut mapping| *column(&mut mapping))
+                    .map(|index| index.to_string())
+                    .unwrap_or_default()
+            }
+            on:change=move |ev| {
+                let index = event_target_value(&ev).parse::<usize>().ok();
+                options
+                    .update(|options| {
+                        *column(options.mapping.get_or_insert_with(Default::default)) = index;
+                    });
+            }
+        >
+            <option value="">"Not in the file"</option>
+            {headers
+                .into_iter()
+                .enumerate()
+                .map(|(index, header)| {
+                    let name = if header.is_empty() {
+                        format!("Column {}", index + 1)
+                    } else {
+                        header
+                    };
+                    view! { <option value=index.to_string()>{name}</option> }
+                })
+                .collect::<Vec<_>>()}
+        </select>
+    }
+}
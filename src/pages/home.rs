@@ -1,12 +1,24 @@
+use chrono::prelude::*;
 use leptos::prelude::*;
+use crate::components::{stopwatch::Stopwatch, vacation_summary::VacationSummary};
 use crate::pages::calendars::Calendar;
 
 #[component]
 pub fn HomePage() -> impl IntoView {
+    let today = Local::now().date_naive();
+    let (entries_changed, set_entries_changed) = signal(0);
+    let (timer_stopped, set_timer_stopped) = signal(0);
+
     view! {
         <h1>"Hours Counter"</h1>
-        <Calendar/>
+        <Stopwatch on_stop=move || set_timer_stopped.update(|count| *count += 1)/>
+        <div class="home">
+            <Calendar
+                month=(today.year(), today.month())
+                on_change=move || set_entries_changed.update(|count| *count += 1)
+                reload=timer_stopped
+            />
+            <VacationSummary year=today.year() refresh=entries_changed/>
+        </div>
     }
 }
-
-
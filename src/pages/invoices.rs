@@ -0,0 +1,146 @@
+use chrono::prelude::*;
+use leptos::prelude::*;
+
+use crate::api::{load_invoices, load_projects, IssueInvoice};
+use crate::models::billing::format_amount;
+
+#[component]
+pub fn InvoicesPage() -> impl IntoView {
+    let issue_invoice = ServerAction::<IssueInvoice>::new();
+    let invoices = Resource::new(move || issue_invoice.version().get(), |_| load_invoices());
+    let projects = Resource::new(|| (), |_| load_projects());
+
+    // Invoices are usually issued for the month that just ended
+    let last_month = Local::now()
+        .date_naive()
+        .with_day(1)
+        .and_then(|first| first.pred_opt())
+        .unwrap_or_default();
+    let period = RwSignal::new((last_month.year(), last_month.month()));
+    let project = RwSignal::new(None::<u64>);
+
+    let issue = move |_| {
+        if let Some(project) = project.get() {
+            let (year, month) = period.get();
+            issue_invoice.dispatch(IssueInvoice {
+                project,
+                year,
+                month,
+            });
+        }
+    };
+    let status = move || {
+        issue_invoice.value().get().map(|result| match result {
+            Ok(invoice) => {
+                view! { <p class="saved">{format!("Invoice {} issued.", invoice.number())}</p> }
+                    .into_any()
+            }
+            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+        })
+    };
+    let project_name = move |id: u64| {
+        projects
+            .get()
+            .and_then(Result::ok)
+            .and_then(|projects| projects.into_iter().find(|project| project.id == id))
+            .map(|project| project.name)
+            .unwrap_or_else(|| format!("Project #{id}"))
+    };
+
+    view! {
+        <h1>"Invoices"</h1>
+        <div class="settings">
+            <h2>"New invoice"</h2>
+            <div class="settings-grid">
+                <label>"Project"</label>
+                <Transition fallback=|| ()>
+                    <select on:change=move |ev| {
+                        project.set(event_target_value(&ev).parse::<u64>().ok())
+                    }>
+                        <option value="">"Pick a project"</option>
+                        {move || {
+                            projects
+                                .get()
+                                .and_then(Result::ok)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .filter(|project| !project.rates.is_empty())
+                                .map(|project| {
+                                    view! {
+                                        <option value=project.id.to_string()>{project.name}</option>
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                        }}
+                    </select>
+                </Transition>
+                <label>"Month"</label>
+                <input
+                    type="month"
+                    prop:value=move || {
+                        let (year, month) = period.get();
+                        format!("{year:04}-{month:02}")
+                    }
+                    on:change=move |ev| {
+                        let value = format!("{}-01", event_target_value(&ev));
+                        if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                            period.set((date.year(), date.month()));
+                        }
+                    }
+                />
+            </div>
+            {status}
+            <div class="modal-buttons">
+                <button on:click=issue disabled=move || project.get().is_none()>
+                    "Issue invoice"
+                </button>
+            </div>
+        </div>
+        <div class="settings">
+            <h2>"Issued invoices"</h2>
+            <Transition fallback=|| view! { <p>"Loading invoices..."</p> }>
+                {move || {
+                    invoices
+                        .get()
+                        .map(|invoices| match invoices {
+                            Ok(invoices) => {
+                                view! {
+                                    <table class="invoices">
+                                        {invoices
+                                            .into_iter()
+                                            .rev()
+                                            .map(|invoice| {
+                                                let number = invoice.number();
+                                                let (year, month) = invoice.period;
+                                                view! {
+                                                    <tr>
+                                                        <td>{number.clone()}</td>
+                                                        <td>{invoice.issued.to_string()}</td>
+                                                        <td>{format!("{year:04}-{month:02}")}</td>
+                                                        <td>{project_name(invoice.project)}</td>
+                                                        <td>{invoice.client.lines().next().unwrap_or_default().to_string()}</td>
+                                                        <td>{format_amount(invoice.total_cents(), &invoice.currency)}</td>
+                                                        <td>
+                                                            <a href=format!("/invoices/{number}.html") rel="external" target="_blank">
+                                                                "HTML"
+                                                            </a>
+                                                            " "
+                                                            <a href=format!("/invoices/{number}.pdf") rel="external" target="_blank">
+                                                                "PDF"
+                                                            </a>
+                                                        </td>
+                                                    </tr>
+                                                }
+                                            })
+                                            .collect::<Vec<_>>()}
+                                    </table>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
@@ -0,0 +1,35 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+use crate::models::calendar_state::CALENDAR_YEARS;
+use crate::pages::calendars::Calendar;
+use crate::pages::not_found::NotFound;
+
+/// Parse the `:year` and `:month` route segments
+fn parse_month(year: &str, month: &str) -> Option<(i32, u32)> {
+    let year = year.parse::<i32>().ok().filter(|year| CALENDAR_YEARS.contains(year))?;
+    let month = month.parse::<u32>().ok().filter(|month| (1..=12).contains(month))?;
+    Some((year, month))
+}
+
+#[component]
+pub fn MonthPage() -> impl IntoView {
+    let params = use_params_map();
+    let month = Memo::new(move |_| {
+        params.with(|params| {
+            parse_month(
+                &params.get("year").unwrap_or_default(),
+                &params.get("month").unwrap_or_default(),
+            )
+        })
+    });
+
+    move || match month.get() {
+        Some(_) => view! {
+            <h1>"Hours Counter"</h1>
+            <Calendar month=Signal::derive(move || month.get().unwrap_or_default())/>
+        }
+        .into_any(),
+        None => view! { <NotFound/> }.into_any(),
+    }
+}
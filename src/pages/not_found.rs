@@ -0,0 +1,19 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+#[component]
+pub fn NotFound() -> impl IntoView {
+    // Let the server answer with a real 404 instead of a 200 page
+    #[cfg(feature = "ssr")]
+    if let Some(response) = use_context::<leptos_axum::ResponseOptions>() {
+        response.set_status(axum::http::StatusCode::NOT_FOUND);
+    }
+
+    view! {
+        <div class="not-found">
+            <h1>"Page not found"</h1>
+            <p>"There is no calendar page at this address."</p>
+            <A href="/">"Back to the current month"</A>
+        </div>
+    }
+}
@@ -0,0 +1,750 @@
+use chrono::prelude::*;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::api::{
+    get_settings, load_adjustments, load_projects, read_days_off, AddAdjustment, AddProject,
+    DeleteAdjustment, SaveSettings, UpdateProject,
+};
+use crate::components::file_input::read_chosen_file;
+use crate::models::{
+    balance::format_delta,
+    billing::HourlyRate,
+    flexitime::FlexitimeAccount,
+    friday_cycle::FridayCycle,
+    holidays::{Holiday, HolidayPreset, HolidayRule},
+    labour_code::{NormMode, SettlementPeriod},
+    project::Project,
+    user_settings::UserSettings,
+    work_schedule::WorkSchedule,
+};
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+#[component]
+pub fn SettingsPage() -> impl IntoView {
+    let save_settings = ServerAction::<SaveSettings>::new();
+    let settings = Resource::new(
+        move || save_settings.version().get(),
+        |_| get_settings(),
+    );
+
+    view! {
+        <h1>"Settings"</h1>
+        <Transition fallback=|| view! { <p>"Loading settings..."</p> }>
+            {move || {
+                settings
+                    .get()
+                    .map(|settings| match settings {
+                        Ok(settings) => {
+                            view! { <SettingsForm settings save_settings/> }.into_any()
+                        }
+                        Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                    })
+            }}
+        </Transition>
+        <BalanceAdjustments/>
+        <Projects/>
+    }
+}
+
+#[component]
+fn SettingsForm(
+    settings: UserSettings,
+    save_settings: ServerAction<SaveSettings>,
+) -> impl IntoView {
+    let schedule = settings.work_schedule.clone();
+    let daily_hours = RwSignal::new(schedule.daily_minutes.map(|minutes| minutes as f32 / 60.0));
+    let friday_anchor = RwSignal::new(schedule.friday_cycle.anchor());
+    let friday_interval = RwSignal::new(schedule.friday_cycle.interval_weeks());
+    let holiday_preset = RwSignal::new(settings.holiday_preset);
+    let days_off = RwSignal::new(settings.days_off.clone());
+    let days_off_status = RwSignal::new(None::<Result<String, String>>);
+    let norm_mode = RwSignal::new(settings.norm_mode);
+    let settlement_period = RwSignal::new(settings.settlement_period);
+    let flexitime_start = RwSignal::new(settings.flexitime.start);
+    let opening_hours = RwSignal::new(settings.flexitime.opening_minutes as f32 / 60.0);
+    let vacation = RwSignal::new(settings.vacation.clone());
+    let billing = RwSignal::new(settings.billing.clone());
+    let employee_name = RwSignal::new(settings.employee_name.clone());
+    let (form_error, set_form_error) = signal::<Option<String>>(None);
+
+    let save = move |_| {
+        let Some(friday_cycle) = FridayCycle::new(friday_anchor.get(), friday_interval.get())
+        else {
+            set_form_error.set(Some("The first working Friday must be a Friday.".to_string()));
+            return;
+        };
+        set_form_error.set(None);
+        let mut settings = settings.clone();
+        settings.work_schedule = WorkSchedule {
+            daily_minutes: daily_hours.get().map(|hours| (hours * 60.0).round() as u32),
+            friday_cycle,
+        };
+        settings.holiday_preset = holiday_preset.get();
+        settings.days_off = days_off.get();
+        settings.norm_mode = norm_mode.get();
+        settings.settlement_period = settlement_period.get();
+        settings.flexitime = FlexitimeAccount {
+            start: flexitime_start.get(),
+            opening_minutes: (opening_hours.get() * 60.0).round() as i32,
+        };
+        settings.vacation = vacation.get();
+        settings.billing = billing.get();
+        settings.employee_name = employee_name.get().trim().to_string();
+        save_settings.dispatch(SaveSettings { settings });
+    };
+
+    let load_days_off = move |ev: leptos::ev::Event| {
+        read_chosen_file(&ev, move |text| {
+            spawn_local(async move {
+                let status = match read_days_off(text).await {
+                    Ok(import) => {
+                        let mut added = 0;
+                        days_off.update(|days_off| {
+                            for day_off in import.days_off {
+                                if !days_off.contains(&day_off) {
+                                    days_off.push(day_off);
+                                    added += 1;
+                                }
+                            }
+                        });
+                        let mut message =
+                            format!("{added} days off added, save the settings to keep them.");
+                        for reason in import.skipped {
+                            message.push_str(&format!(" Skipped: {reason}."));
+                        }
+                        Ok(message)
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                days_off_status.set(Some(status));
+            });
+        });
+    };
+
+    let status = move || {
+        form_error
+            .get()
+            .map(|message| view! { <p class="error">{message}</p> }.into_any())
+            .or_else(|| {
+                save_settings.value().get().map(|result| match result {
+                    Ok(()) => view! { <p class="saved">"Settings saved."</p> }.into_any(),
+                    Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                })
+            })
+    };
+
+    view! {
+        <div class="settings">
+            <h2>"Work schedule"</h2>
+            <div class="settings-grid">
+                {WEEKDAY_NAMES
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| {
+                        view! {
+                            <label>{*name}</label>
+                            <input
+                                type="number"
+                                step="0.25"
+                                min="0"
+                                max="24"
+                                prop:value=move || daily_hours.get()[index]
+                                on:input=move |ev| {
+                                    if let Ok(hours) = event_target_value(&ev).parse::<f32>() {
+                                        daily_hours.update(|days| days[index] = hours.clamp(0.0, 24.0));
+                                    }
+                                }
+                            />
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+            <h2>"Working Fridays"</h2>
+            <div class="settings-grid">
+                <label>"A working Friday"</label>
+                <input
+                    type="date"
+                    prop:value=move || friday_anchor.get().format("%Y-%m-%d").to_string()
+                    on:change=move |ev| {
+                        if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") {
+                            friday_anchor.set(date);
+                        }
+                    }
+                />
+                <label>"Every n-th Friday is worked"</label>
+                <input
+                    type="number"
+                    min="0"
+                    max="52"
+                    prop:value=move || friday_interval.get()
+                    on:input=move |ev| {
+                        if let Ok(interval) = event_target_value(&ev).parse::<u32>() {
+                            friday_interval.set(interval);
+                        }
+                    }
+                />
+            </div>
+            <h2>"Public holidays"</h2>
+            <div class="settings-grid">
+                <label>"Holiday calendar"</label>
+                <select
+                    prop:value=move || format!("{:?}", holiday_preset.get())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(preset) = HolidayPreset::ALL
+                            .into_iter()
+                            .find(|preset| format!("{preset:?}") == value)
+                        {
+                            holiday_preset.set(preset);
+                        }
+                    }
+                >
+                    {HolidayPreset::ALL
+                        .into_iter()
+                        .map(|preset| {
+                            view! { <option value=format!("{preset:?}")>{preset.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            </div>
+            <h3>"Company days off"</h3>
+            <div class="settings-grid">
+                <label>"Import from an iCalendar file"</label>
+                <input type="file" accept=".ics,text/calendar" on:change=load_days_off/>
+            </div>
+            {move || {
+                days_off_status
+                    .get()
+                    .map(|status| match status {
+                        Ok(message) => view! { <p class="saved">{message}</p> }.into_any(),
+                        Err(message) => view! { <p class="error">{message}</p> }.into_any(),
+                    })
+            }}
+            <table class="adjustments">
+                {move || {
+                    days_off
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, day_off)| {
+                            view! {
+                                <tr>
+                                    <td>{day_off_dates(&day_off)}</td>
+                                    <td>{day_off.name}</td>
+                                    <td>
+                                        <button on:click=move |_| {
+                                            days_off.update(|days_off| {
+                                                days_off.remove(index);
+                                            })
+                                        }>"Remove"</button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </table>
+            <h2>"Expected hours"</h2>
+            <div class="settings-grid">
+                <label>"Norm shown in the calendar"</label>
+                <select
+                    prop:value=move || format!("{:?}", norm_mode.get())
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(mode) = NormMode::ALL
+                            .into_iter()
+                            .find(|mode| format!("{mode:?}") == value)
+                        {
+                            norm_mode.set(mode);
+                        }
+                    }
+                >
+                    {NormMode::ALL
+                        .into_iter()
+                        .map(|mode| {
+                            view! { <option value=format!("{mode:?}")>{mode.label()}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+                <label>"Settlement period"</label>
+                <select
+                    prop:value=move || settlement_period.get().months().to_string()
+                    on:change=move |ev| {
+                        if let Some(period) = event_target_value(&ev)
+                            .parse::<u32>()
+                            .ok()
+                            .and_then(SettlementPeriod::new)
+                        {
+                            settlement_period.set(period);
+                        }
+                    }
+                >
+                    {SettlementPeriod::LENGTHS
+                        .into_iter()
+                        .map(|months| {
+                            let label = if months == 1 {
+                                "1 month".to_string()
+                            } else {
+                                format!("{months} months")
+                            };
+                            view! { <option value=months.to_string()>{label}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            </div>
+            <h2>"Flexitime account"</h2>
+            <div class="settings-grid">
+                <label>"Counted from (empty for the first logged day)"</label>
+                <input
+                    type="date"
+                    prop:value=move || {
+                        flexitime_start
+                            .get()
+                            .map(|date| date.format("%Y-%m-%d").to_string())
+                            .unwrap_or_default()
+                    }
+                    on:change=move |ev| {
+                        flexitime_start
+                            .set(NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d").ok());
+                    }
+                />
+                <label>"Opening balance in hours"</label>
+                <input
+                    type="number"
+                    step="0.25"
+                    prop:value=move || opening_hours.get()
+                    on:input=move |ev| {
+                        if let Ok(hours) = event_target_value(&ev).parse::<f32>() {
+                            opening_hours.set(hours);
+                        }
+                    }
+                />
+            </div>
+            <h2>"Vacation"</h2>
+            <div class="settings-grid">
+                <label>"Days per year"</label>
+                <input
+                    type="number"
+                    step="0.5"
+                    min="0"
+                    prop:value=move || vacation.get().annual_days
+                    on:input=move |ev| {
+                        if let Ok(days) = event_target_value(&ev).parse::<f32>() {
+                            vacation.update(|vacation| vacation.annual_days = days.max(0.0));
+                        }
+                    }
+                />
+                <label>"First year tracked (empty for the current year)"</label>
+                <input
+                    type="number"
+                    min="1970"
+                    max="9999"
+                    prop:value=move || {
+                        vacation.get().first_year.map(|year| year.to_string()).unwrap_or_default()
+                    }
+                    on:change=move |ev| {
+                        let first_year = event_target_value(&ev).parse::<i32>().ok();
+                        vacation.update(|vacation| vacation.first_year = first_year);
+                    }
+                />
+                <label>"Days carried into the first year"</label>
+                <input
+                    type="number"
+                    step="0.5"
+                    prop:value=move || vacation.get().carried_over_days
+                    on:input=move |ev| {
+                        if let Ok(days) = event_target_value(&ev).parse::<f32>() {
+                            vacation.update(|vacation| vacation.carried_over_days = days);
+                        }
+                    }
+                />
+                <label>"Most days carried into the next year (empty for no limit)"</label>
+                <input
+                    type="number"
+                    step="0.5"
+                    min="0"
+                    prop:value=move || {
+                        vacation
+                            .get()
+                            .carry_over_limit
+                            .map(|days| days.to_string())
+                            .unwrap_or_default()
+                    }
+                    on:change=move |ev| {
+                        let limit = event_target_value(&ev).parse::<f32>().ok().map(|days| days.max(0.0));
+                        vacation.update(|vacation| vacation.carry_over_limit = limit);
+                    }
+                />
+                <label>"Employment start, pro-rating its year"</label>
+                <input
+                    type="date"
+                    prop:value=move || {
+                        vacation
+                            .get()
+                            .employment_start
+                            .map(|date| date.format("%Y-%m-%d").to_string())
+                            .unwrap_or_default()
+                    }
+                    on:change=move |ev| {
+                        let start = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d").ok();
+                        vacation.update(|vacation| vacation.employment_start = start);
+                    }
+                />
+            </div>
+            <h2>"Invoices"</h2>
+            <div class="settings-grid">
+                <label>"Currency"</label>
+                <input
+                    type="text"
+                    prop:value=move || billing.get().currency
+                    on:change=move |ev| {
+                        let currency = event_target_value(&ev).trim().to_string();
+                        billing.update(|billing| billing.currency = currency);
+                    }
+                />
+                <label>"Your name and address"</label>
+                <textarea
+                    prop:value=move || billing.get().issuer
+                    on:change=move |ev| {
+                        let issuer = event_target_value(&ev);
+                        billing.update(|billing| billing.issuer = issuer);
+                    }
+                ></textarea>
+                <label>"Days until payment is due"</label>
+                <input
+                    type="number"
+                    min="0"
+                    prop:value=move || billing.get().payment_days
+                    on:input=move |ev| {
+                        if let Ok(days) = event_target_value(&ev).parse::<u32>() {
+                            billing.update(|billing| billing.payment_days = days);
+                        }
+                    }
+                />
+            </div>
+            <h2>"Timesheets"</h2>
+            <div class="settings-grid">
+                <label>"Your name"</label>
+                <input
+                    type="text"
+                    prop:value=move || employee_name.get()
+                    on:change=move |ev| employee_name.set(event_target_value(&ev))
+                />
+            </div>
+            {status}
+            <div class="modal-buttons">
+                <button on:click=save>"Save"</button>
+            </div>
+        </div>
+    }
+}
+
+/// When a day off falls, e.g. `2026-12-24` or `Every year on December 24 from 2026`
+fn day_off_dates(day_off: &Holiday) -> String {
+    let mut dates = match &day_off.rule {
+        HolidayRule::Date { date } => return date.format("%Y-%m-%d").to_string(),
+        HolidayRule::Fixed { month, day } => NaiveDate::from_ymd_opt(2000, *month, *day)
+            .map(|date| format!("Every year on {}", date.format("%B %-d")))
+            .unwrap_or_default(),
+        HolidayRule::Easter { offset_days } => {
+            format!("Every year {offset_days} days after Easter Sunday")
+        }
+    };
+    if let Some(since) = day_off.since {
+        dates.push_str(&format!(" from {since}"));
+    }
+    if let Some(until) = day_off.until {
+        dates.push_str(&format!(" until {until}"));
+    }
+    dates
+}
+
+/// Manual changes of the flexitime balance, e.g. overtime paid out
+#[component]
+fn BalanceAdjustments() -> impl IntoView {
+    let add_adjustment = ServerAction::<AddAdjustment>::new();
+    let delete_adjustment = ServerAction::<DeleteAdjustment>::new();
+    let adjustments = Resource::new(
+        move || (add_adjustment.version().get(), delete_adjustment.version().get()),
+        |_| load_adjustments(),
+    );
+    let date = RwSignal::new(Local::now().date_naive());
+    let hours = RwSignal::new(0.0_f32);
+    let note = RwSignal::new(String::new());
+
+    let add = move |_| {
+        let date = date.get();
+        add_adjustment.dispatch(AddAdjustment {
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+            minutes: (hours.get() * 60.0).round() as i32,
+            note: note.get(),
+        });
+        hours.set(0.0);
+        note.set(String::new());
+    };
+
+    view! {
+        <div class="settings">
+            <h2>"Balance adjustments"</h2>
+            <Transition fallback=|| view! { <p>"Loading adjustments..."</p> }>
+                {move || {
+                    adjustments
+                        .get()
+                        .map(|adjustments| match adjustments {
+                            Ok(adjustments) => {
+                                view! {
+                                    <table class="adjustments">
+                                        {adjustments
+                                            .into_iter()
+                                            .map(|adjustment| {
+                                                let (year, month, day) = adjustment.date;
+                                                let id = adjustment.id;
+                                                view! {
+                                                    <tr>
+                                                        <td>{format!("{year:04}-{month:02}-{day:02}")}</td>
+                                                        <td>{format_delta(adjustment.minutes as f32 / 60.0)}</td>
+                                                        <td>{adjustment.note}</td>
+                                                        <td>
+                                                            <button on:click=move |_| {
+                                                                delete_adjustment.dispatch(DeleteAdjustment { id });
+                                                            }>"Remove"</button>
+                                                        </td>
+                                                    </tr>
+                                                }
+                                            })
+                                            .collect::<Vec<_>>()}
+                                    </table>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+            <div class="settings-grid">
+                <label>"Date"</label>
+                <input
+                    type="date"
+                    prop:value=move || date.get().format("%Y-%m-%d").to_string()
+                    on:change=move |ev| {
+                        if let Ok(value) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") {
+                            date.set(value);
+                        }
+                    }
+                />
+                <label>"Hours, negative when taken off the balance"</label>
+                <input
+                    type="number"
+                    step="0.25"
+                    prop:value=move || hours.get()
+                    on:input=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse::<f32>() {
+                            hours.set(value);
+                        }
+                    }
+                />
+                <label>"Note"</label>
+                <input
+                    type="text"
+                    placeholder="e.g. overtime paid out"
+                    prop:value=move || note.get()
+                    on:input=move |ev| note.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="modal-buttons">
+                <button on:click=add>"Add adjustment"</button>
+            </div>
+        </div>
+    }
+}
+
+/// List of projects to rename, recolour or archive, and a form to add one
+#[component]
+fn Projects() -> impl IntoView {
+    let add_project = ServerAction::<AddProject>::new();
+    let update_project = ServerAction::<UpdateProject>::new();
+    let projects = Resource::new(
+        move || (add_project.version().get(), update_project.version().get()),
+        |_| load_projects(),
+    );
+    let name = RwSignal::new(String::new());
+    let colour = RwSignal::new("#3a7bd5".to_string());
+
+    let add = move |_| {
+        add_project.dispatch(AddProject {
+            name: name.get(),
+            colour: colour.get(),
+        });
+        name.set(String::new());
+    };
+
+    view! {
+        <div class="settings">
+            <h2>"Projects"</h2>
+            <Transition fallback=|| view! { <p>"Loading projects..."</p> }>
+                {move || {
+                    projects
+                        .get()
+                        .map(|projects| match projects {
+                            Ok(projects) => {
+                                view! {
+                                    <table class="projects">
+                                        {projects
+                                            .into_iter()
+                                            .map(|project| view! { <ProjectRow project update_project/> })
+                                            .collect::<Vec<_>>()}
+                                    </table>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Transition>
+            <div class="settings-grid">
+                <label>"Name"</label>
+                <input
+                    type="text"
+                    prop:value=move || name.get()
+                    on:input=move |ev| name.set(event_target_value(&ev))
+                />
+                <label>"Colour"</label>
+                <input
+                    type="color"
+                    prop:value=move || colour.get()
+                    on:input=move |ev| colour.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="modal-buttons">
+                <button on:click=add>"Add project"</button>
+            </div>
+        </div>
+    }
+}
+
+/// Editable name, colour, active flag, client and hourly rates of a project
+#[component]
+fn ProjectRow(project: Project, update_project: ServerAction<UpdateProject>) -> impl IntoView {
+    let stored = StoredValue::new(project.clone());
+    let update = move |change: &dyn Fn(&mut Project)| {
+        let mut project = stored.get_value();
+        change(&mut project);
+        update_project.dispatch(UpdateProject { project });
+    };
+    let rate_from = RwSignal::new(Local::now().date_naive());
+    let rate = RwSignal::new(0.0_f64);
+
+    view! {
+        <tr class:inactive=!project.active>
+            <td>
+                <input
+                    type="color"
+                    prop:value=project.colour.clone()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        update(&|project| project.colour = value.clone());
+                    }
+                />
+            </td>
+            <td>
+                <input
+                    type="text"
+                    prop:value=project.name.clone()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        update(&|project| project.name = value.clone());
+                    }
+                />
+            </td>
+            <td>
+                <input
+                    type="text"
+                    placeholder="Client"
+                    prop:value=project.client.clone()
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        update(&|project| project.client = value.trim().to_string());
+                    }
+                />
+            </td>
+            <td class="rates">
+                {project
+                    .rates
+                    .iter()
+                    .map(|hourly_rate| {
+                        let from = hourly_rate.from;
+                        view! {
+                            <div>
+                                {format!(
+                                    "From {from}: {}.{:02} per hour",
+                                    hourly_rate.cents / 100,
+                                    hourly_rate.cents % 100,
+                                )}
+                                <button on:click=move |_| {
+                                    update(&|project| project.rates.retain(|rate| rate.from != from))
+                                }>"Remove"</button>
+                            </div>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+                <div>
+                    <input
+                        type="date"
+                        prop:value=move || rate_from.get().format("%Y-%m-%d").to_string()
+                        on:change=move |ev| {
+                            if let Ok(date) = NaiveDate::parse_from_str(&event_target_value(&ev), "%Y-%m-%d") {
+                                rate_from.set(date);
+                            }
+                        }
+                    />
+                    <input
+                        type="number"
+                        step="0.01"
+                        min="0"
+                        prop:value=move || rate.get()
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                rate.set(value.max(0.0));
+                            }
+                        }
+                    />
+                    <button on:click=move |_| {
+                        let hourly_rate = HourlyRate {
+                            from: rate_from.get(),
+                            cents: (rate.get() * 100.0).round() as u64,
+                        };
+                        update(&|project| {
+                            project.rates.retain(|rate| rate.from != hourly_rate.from);
+                            project.rates.push(hourly_rate.clone());
+                        })
+                    }>"Add rate"</button>
+                </div>
+            </td>
+            <td>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=project.active
+                        on:change=move |ev| {
+                            let active = event_target_checked(&ev);
+                            update(&|project| project.active = active);
+                        }
+                    />
+                    "Active"
+                </label>
+            </td>
+        </tr>
+    }
+}
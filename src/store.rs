@@ -0,0 +1,417 @@
+use crate::models::{
+    billing::Invoice, calendar_state::CalendarDate, day_data::DayData,
+    flexitime::BalanceAdjustment, project::Project, timer::Timer, user_settings::UserSettings,
+};
+use chrono::NaiveDateTime;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub mod json_file;
+pub mod memory;
+pub mod sqlite;
+
+pub use json_file::JsonFileStore;
+pub use memory::MemoryStore;
+pub use sqlite::SqliteStore;
+
+/// Storage backend for logged hours, working Friday overrides and user settings
+pub trait TimeStore: Send + Sync {
+    /// Get all logged days between `from` and `to`, both inclusive, in date order
+    fn get_range(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, DayData)>, StoreError>;
+
+    /// Insert or replace the time logged on a date
+    fn upsert_day(&self, date: CalendarDate, data: DayData) -> Result<(), StoreError>;
+
+    /// Remove the time logged on a date
+    fn delete_day(&self, date: CalendarDate) -> Result<(), StoreError>;
+
+    /// Get the Fridays between `from` and `to` whose working status overrides the Friday cycle,
+    /// in date order
+    fn list_friday_overrides(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, bool)>, StoreError>;
+
+    /// Override whether a Friday is worked, or go back to the Friday cycle with `None`
+    fn set_friday_override(
+        &self,
+        date: CalendarDate,
+        working: Option<bool>,
+    ) -> Result<(), StoreError>;
+
+    /// Get the saved settings of `user`, `None` if they were never saved
+    fn load_settings(&self, user: &str) -> Result<Option<UserSettings>, StoreError>;
+
+    /// Replace the saved settings of `user`
+    fn save_settings(&self, user: &str, settings: &UserSettings) -> Result<(), StoreError>;
+
+    /// Get the flexitime balance adjustments between `from` and `to`, both inclusive, in date
+    /// order
+    fn list_adjustments(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<BalanceAdjustment>, StoreError>;
+
+    /// Record a flexitime balance adjustment, returning it with its new id
+    fn add_adjustment(
+        &self,
+        date: CalendarDate,
+        minutes: i32,
+        note: &str,
+    ) -> Result<BalanceAdjustment, StoreError>;
+
+    /// Remove a flexitime balance adjustment
+    fn delete_adjustment(&self, id: u64) -> Result<(), StoreError>;
+
+    /// Get the stopwatch, `None` if it is not in use
+    fn load_timer(&self) -> Result<Option<Timer>, StoreError>;
+
+    /// Replace the stopwatch, or remove it with `None`
+    fn save_timer(&self, timer: Option<&Timer>) -> Result<(), StoreError>;
+
+    /// Log the stopwatch's runs up to `now` onto the days they cover, at most 24 hours a day, and
+    /// remove it, as a single change; `false` if it was not in use
+    fn stop_timer(&self, now: NaiveDateTime) -> Result<bool, StoreError>;
+
+    /// Get all projects, in the order they were added
+    fn list_projects(&self) -> Result<Vec<Project>, StoreError>;
+
+    /// Add an active project, returning it with its new id
+    fn add_project(&self, name: &str, colour: &str) -> Result<Project, StoreError>;
+
+    /// Replace the name, colour, active flag, client and rates of a project
+    fn update_project(&self, project: &Project) -> Result<(), StoreError>;
+
+    /// Get all issued invoices, in the order they were issued
+    fn list_invoices(&self) -> Result<Vec<Invoice>, StoreError>;
+
+    /// Store an invoice under the next number of the year it is issued in, returning it numbered;
+    /// `None` if its project was already invoiced for the same month
+    fn issue_invoice(&self, invoice: Invoice) -> Result<Option<Invoice>, StoreError>;
+}
+
+/// Error raised by a storage backend
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Sqlite(rusqlite::Error),
+    Config(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "storage I/O error: {e}"),
+            StoreError::Json(e) => write!(f, "invalid hours file: {e}"),
+            StoreError::Sqlite(e) => write!(f, "database error: {e}"),
+            StoreError::Config(message) => write!(f, "invalid store configuration: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(e: serde_json::Error) -> Self {
+        StoreError::Json(e)
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        StoreError::Sqlite(e)
+    }
+}
+
+/// Available storage backends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreKind {
+    Memory,
+    JsonFile,
+    Sqlite,
+}
+
+impl StoreKind {
+    /// Default data location of the backend, relative to the working directory
+    pub fn default_path(&self) -> &'static str {
+        match self {
+            StoreKind::Memory => "",
+            StoreKind::JsonFile => "data/hours.json",
+            StoreKind::Sqlite => "data/hours.sqlite",
+        }
+    }
+}
+
+impl FromStr for StoreKind {
+    type Err = StoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(StoreKind::Memory),
+            "json" => Ok(StoreKind::JsonFile),
+            "sqlite" => Ok(StoreKind::Sqlite),
+            other => Err(StoreError::Config(format!(
+                "unknown store `{other}`, expected `memory`, `json` or `sqlite`"
+            ))),
+        }
+    }
+}
+
+/// Server configuration selecting the storage backend
+#[derive(Clone, Debug)]
+pub struct StoreConfig {
+    pub kind: StoreKind,
+    pub path: PathBuf,
+}
+
+impl StoreConfig {
+    /// Read the configuration from `HOURS_COUNTER_STORE` and `HOURS_COUNTER_DATA`
+    pub fn from_env() -> Result<Self, StoreError> {
+        let kind = match std::env::var("HOURS_COUNTER_STORE") {
+            Ok(kind) => kind.parse()?,
+            Err(_) => StoreKind::JsonFile,
+        };
+        let path = std::env::var("HOURS_COUNTER_DATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(kind.default_path()));
+
+        Ok(Self { kind, path })
+    }
+
+    /// Open the configured backend
+    pub fn open(&self) -> Result<Arc<dyn TimeStore>, StoreError> {
+        Ok(match self.kind {
+            StoreKind::Memory => Arc::new(MemoryStore::new()),
+            StoreKind::JsonFile => Arc::new(JsonFileStore::open(&self.path)?),
+            StoreKind::Sqlite => Arc::new(SqliteStore::open(&self.path)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Run a test against every backend, each with its own empty data file
+    fn with_each_store(test: impl Fn(&dyn TimeStore)) {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "hours_counter-store-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        test(&MemoryStore::new());
+        test(&JsonFileStore::open(dir.join("hours.json")).unwrap());
+        test(&SqliteStore::open(dir.join("hours.sqlite")).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn days_are_listed_in_date_order_within_the_range() {
+        with_each_store(|store| {
+            store.upsert_day((2025, 3, 1), DayData::new(1, 0)).unwrap();
+            store.upsert_day((2025, 2, 28), DayData::new(2, 0)).unwrap();
+            store.upsert_day((2025, 2, 3), DayData::new(3, 0)).unwrap();
+            store.upsert_day((2025, 1, 31), DayData::new(4, 0)).unwrap();
+
+            let days = store.get_range((2025, 2, 1), (2025, 3, 1)).unwrap();
+            let dates = days.iter().map(|(date, _)| *date).collect::<Vec<_>>();
+            assert_eq!(dates, vec![(2025, 2, 3), (2025, 2, 28), (2025, 3, 1)]);
+            assert!(store
+                .get_range((2025, 3, 1), (2025, 2, 1))
+                .unwrap()
+                .is_empty());
+        });
+    }
+
+    #[test]
+    fn days_are_replaced_and_deleted() {
+        with_each_store(|store| {
+            store.upsert_day((2025, 2, 3), DayData::new(8, 0)).unwrap();
+            store.upsert_day((2025, 2, 3), DayData::new(6, 30)).unwrap();
+
+            let days = store.get_range((2025, 2, 3), (2025, 2, 3)).unwrap();
+            assert_eq!(days.len(), 1);
+            assert_eq!((days[0].1.hours(), days[0].1.minutes()), (6, 30));
+
+            store.delete_day((2025, 2, 3)).unwrap();
+            store.delete_day((2025, 2, 4)).unwrap();
+            assert!(store
+                .get_range((2025, 2, 1), (2025, 2, 28))
+                .unwrap()
+                .is_empty());
+        });
+    }
+
+    #[test]
+    fn friday_overrides_are_set_and_cleared() {
+        with_each_store(|store| {
+            store.set_friday_override((2025, 2, 7), Some(true)).unwrap();
+            store
+                .set_friday_override((2025, 2, 14), Some(false))
+                .unwrap();
+            store
+                .set_friday_override((2025, 2, 21), Some(true))
+                .unwrap();
+            store.set_friday_override((2025, 2, 21), None).unwrap();
+
+            assert_eq!(
+                store
+                    .list_friday_overrides((2025, 2, 1), (2025, 2, 28))
+                    .unwrap(),
+                vec![((2025, 2, 7), true), ((2025, 2, 14), false)]
+            );
+        });
+    }
+
+    #[test]
+    fn settings_are_saved() {
+        with_each_store(|store| {
+            assert_eq!(store.load_settings("").unwrap(), None);
+
+            let settings = UserSettings {
+                employee_name: "Jan Kowalski".to_string(),
+                ..UserSettings::default()
+            };
+            store.save_settings("", &settings).unwrap();
+            assert_eq!(store.load_settings("").unwrap(), Some(settings.clone()));
+            assert_eq!(store.load_settings("anna").unwrap(), None);
+
+            let mut part_time = UserSettings::default();
+            part_time.work_schedule.daily_minutes[..5].fill(6 * 60);
+            store.save_settings("anna", &part_time).unwrap();
+            assert_eq!(store.load_settings("anna").unwrap(), Some(part_time));
+            assert_eq!(store.load_settings("").unwrap(), Some(settings));
+        });
+    }
+
+    #[test]
+    fn stopping_the_timer_logs_its_runs_once() {
+        with_each_store(|store| {
+            let at = |day, hour| {
+                chrono::NaiveDate::from_ymd_opt(2025, 3, day)
+                    .unwrap()
+                    .and_hms_opt(hour, 0, 0)
+                    .unwrap()
+            };
+            store.upsert_day((2025, 3, 3), DayData::new(1, 0)).unwrap();
+            let mut timer = Timer::default();
+            timer.start(at(3, 22));
+            store.save_timer(Some(&timer)).unwrap();
+
+            assert!(store.stop_timer(at(4, 2)).unwrap());
+            assert!(!store.stop_timer(at(4, 3)).unwrap());
+            assert_eq!(store.load_timer().unwrap(), None);
+            let days = store.get_range((2025, 3, 3), (2025, 3, 4)).unwrap();
+            let minutes = days
+                .iter()
+                .map(|(date, data)| (*date, data.hours() * 60 + data.minutes()))
+                .collect::<Vec<_>>();
+            assert_eq!(minutes, vec![((2025, 3, 3), 180), ((2025, 3, 4), 120)]);
+        });
+    }
+
+    #[test]
+    fn a_timer_left_running_for_days_logs_at_most_24_hours_a_day() {
+        with_each_store(|store| {
+            let at = |day, hour| {
+                chrono::NaiveDate::from_ymd_opt(2025, 3, day)
+                    .unwrap()
+                    .and_hms_opt(hour, 0, 0)
+                    .unwrap()
+            };
+            store.upsert_day((2025, 3, 4), DayData::new(8, 0)).unwrap();
+            let mut timer = Timer::default();
+            timer.start(at(3, 9));
+            store.save_timer(Some(&timer)).unwrap();
+
+            assert!(store.stop_timer(at(6, 9)).unwrap());
+            let days = store.get_range((2025, 3, 3), (2025, 3, 6)).unwrap();
+            let minutes = days
+                .iter()
+                .map(|(date, data)| (*date, data.hours() * 60 + data.minutes()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                minutes,
+                vec![
+                    ((2025, 3, 3), 15 * 60),
+                    ((2025, 3, 4), 24 * 60),
+                    ((2025, 3, 5), 24 * 60),
+                    ((2025, 3, 6), 9 * 60),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn invoices_are_numbered_per_year_and_issued_once_per_project_and_month() {
+        with_each_store(|store| {
+            let invoice = |project, period: (i32, u32), issued| Invoice {
+                sequence: 0,
+                issued,
+                due: issued,
+                period,
+                project,
+                client: String::new(),
+                issuer: String::new(),
+                currency: "EUR".to_string(),
+                lines: Vec::new(),
+            };
+            let date =
+                |year, month, day| chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+            let first = store.issue_invoice(invoice(1, (2025, 11), date(2025, 12, 1)));
+            let second = store.issue_invoice(invoice(2, (2025, 11), date(2025, 12, 1)));
+            let again = store.issue_invoice(invoice(1, (2025, 11), date(2025, 12, 2)));
+            let next_year = store.issue_invoice(invoice(1, (2025, 12), date(2026, 1, 2)));
+            assert_eq!(first.unwrap().unwrap().number(), "2025-001");
+            assert_eq!(second.unwrap().unwrap().number(), "2025-002");
+            assert_eq!(again.unwrap(), None);
+            assert_eq!(next_year.unwrap().unwrap().number(), "2026-001");
+            assert_eq!(store.list_invoices().unwrap().len(), 3);
+        });
+    }
+
+    #[test]
+    fn file_backends_keep_the_data_when_reopened() {
+        let dir = std::env::temp_dir().join(format!("hours_counter-reopen-{}", std::process::id()));
+        let json = dir.join("hours.json");
+        let sqlite = dir.join("hours.sqlite");
+
+        JsonFileStore::open(&json)
+            .unwrap()
+            .upsert_day((2025, 2, 3), DayData::new(7, 45))
+            .unwrap();
+        SqliteStore::open(&sqlite)
+            .unwrap()
+            .upsert_day((2025, 2, 3), DayData::new(7, 45))
+            .unwrap();
+        let reopened: [Box<dyn TimeStore>; 2] = [
+            Box::new(JsonFileStore::open(&json).unwrap()),
+            Box::new(SqliteStore::open(&sqlite).unwrap()),
+        ];
+        for store in reopened {
+            let days = store.get_range((2025, 2, 3), (2025, 2, 3)).unwrap();
+            assert_eq!((days[0].1.hours(), days[0].1.minutes()), (7, 45));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,365 @@
+use super::{memory::StoreData, StoreError, TimeStore};
+use crate::models::{
+    billing::Invoice, calendar_state::CalendarDate, day_data::DayData,
+    flexitime::BalanceAdjustment, project::Project, timer::Timer, user_settings::UserSettings,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// A single logged day as written to the hours file
+#[derive(Serialize, Deserialize)]
+struct StoredDay {
+    date: CalendarDate,
+    #[serde(flatten)]
+    data: DayData,
+}
+
+/// A Friday whose working status overrides the Friday cycle
+#[derive(Serialize, Deserialize)]
+struct StoredFridayOverride {
+    date: CalendarDate,
+    working: bool,
+}
+
+/// Layout of the hours file
+#[derive(Default, Serialize, Deserialize)]
+struct StoredFile {
+    days: Vec<StoredDay>,
+    #[serde(default)]
+    friday_overrides: Vec<StoredFridayOverride>,
+    /// Working Fridays, as kept before a Friday could also be overridden to be free
+    #[serde(default, skip_serializing)]
+    working_fridays: Vec<CalendarDate>,
+    /// Settings, as kept before each user had their own
+    #[serde(default, skip_serializing)]
+    settings: Option<UserSettings>,
+    /// Settings of each user
+    #[serde(default)]
+    user_settings: BTreeMap<String, UserSettings>,
+    #[serde(default)]
+    adjustments: Vec<BalanceAdjustment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timer: Option<Timer>,
+    #[serde(default)]
+    projects: Vec<Project>,
+    #[serde(default)]
+    invoices: Vec<Invoice>,
+}
+
+/// Store kept in memory and written through to a JSON file, for single-user setups
+pub struct JsonFileStore {
+    path: PathBuf,
+    data: RwLock<StoreData>,
+}
+
+impl JsonFileStore {
+    /// Open the store at `path`, starting empty if the file does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, StoreError> {
+        let path = path.into();
+        let file = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str::<StoredFile>(&contents).or_else(|e| {
+                // Files written before the store kept anything else hold just the logged days
+                serde_json::from_str::<Vec<StoredDay>>(&contents)
+                    .map(|days| StoredFile {
+                        days,
+                        ..StoredFile::default()
+                    })
+                    .map_err(|_| e)
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => StoredFile::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let data = StoreData {
+            days: file
+                .days
+                .into_iter()
+                .map(|day| (day.date, day.data))
+                .collect(),
+            friday_overrides: file
+                .working_fridays
+                .into_iter()
+                .map(|date| (date, true))
+                .chain(
+                    file.friday_overrides
+                        .into_iter()
+                        .map(|friday| (friday.date, friday.working)),
+                )
+                .collect(),
+            settings: file
+                .settings
+                .map(|settings| (String::new(), settings))
+                .into_iter()
+                .chain(file.user_settings)
+                .collect(),
+            adjustments: file.adjustments,
+            timer: file.timer,
+            projects: file.projects,
+            invoices: file.invoices,
+        };
+
+        Ok(Self {
+            path,
+            data: RwLock::new(data),
+        })
+    }
+
+    /// Write everything to a temporary file and move it over the hours file
+    fn persist(&self, data: &StoreData) -> Result<(), StoreError> {
+        let file = StoredFile {
+            days: data
+                .days
+                .iter()
+                .map(|(date, data)| StoredDay {
+                    date: *date,
+                    data: data.clone(),
+                })
+                .collect(),
+            friday_overrides: data
+                .friday_overrides
+                .iter()
+                .map(|(date, working)| StoredFridayOverride {
+                    date: *date,
+                    working: *working,
+                })
+                .collect(),
+            working_fridays: Vec::new(),
+            settings: None,
+            user_settings: data.settings.clone(),
+            adjustments: data.adjustments.clone(),
+            timer: data.timer.clone(),
+            projects: data.projects.clone(),
+            invoices: data.invoices.clone(),
+        };
+        let contents = serde_json::to_string_pretty(&file)?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl TimeStore for JsonFileStore {
+    fn get_range(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, DayData)>, StoreError> {
+        Ok(self.data.read().unwrap().get_range(from, to))
+    }
+
+    fn upsert_day(&self, date: CalendarDate, data: DayData) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        stored.days.insert(date, data);
+        self.persist(&stored)
+    }
+
+    fn delete_day(&self, date: CalendarDate) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        if stored.days.remove(&date).is_some() {
+            self.persist(&stored)?;
+        }
+        Ok(())
+    }
+
+    fn list_friday_overrides(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, bool)>, StoreError> {
+        Ok(self.data.read().unwrap().list_friday_overrides(from, to))
+    }
+
+    fn set_friday_override(
+        &self,
+        date: CalendarDate,
+        working: Option<bool>,
+    ) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        if stored.set_friday_override(date, working) {
+            self.persist(&stored)?;
+        }
+        Ok(())
+    }
+
+    fn load_settings(&self, user: &str) -> Result<Option<UserSettings>, StoreError> {
+        Ok(self.data.read().unwrap().settings.get(user).cloned())
+    }
+
+    fn save_settings(&self, user: &str, settings: &UserSettings) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        stored.settings.insert(user.to_string(), settings.clone());
+        self.persist(&stored)
+    }
+
+    fn list_adjustments(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<BalanceAdjustment>, StoreError> {
+        Ok(self.data.read().unwrap().list_adjustments(from, to))
+    }
+
+    fn add_adjustment(
+        &self,
+        date: CalendarDate,
+        minutes: i32,
+        note: &str,
+    ) -> Result<BalanceAdjustment, StoreError> {
+        let mut stored = self.data.write().unwrap();
+        let adjustment = stored.add_adjustment(date, minutes, note);
+        self.persist(&stored)?;
+        Ok(adjustment)
+    }
+
+    fn delete_adjustment(&self, id: u64) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        if stored.delete_adjustment(id) {
+            self.persist(&stored)?;
+        }
+        Ok(())
+    }
+
+    fn load_timer(&self) -> Result<Option<Timer>, StoreError> {
+        Ok(self.data.read().unwrap().timer.clone())
+    }
+
+    fn save_timer(&self, timer: Option<&Timer>) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        stored.timer = timer.cloned();
+        self.persist(&stored)
+    }
+
+    fn stop_timer(&self, now: NaiveDateTime) -> Result<bool, StoreError> {
+        let mut stored = self.data.write().unwrap();
+        if !stored.stop_timer(now) {
+            return Ok(false);
+        }
+        self.persist(&stored)?;
+        Ok(true)
+    }
+
+    fn list_projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(self.data.read().unwrap().projects.clone())
+    }
+
+    fn add_project(&self, name: &str, colour: &str) -> Result<Project, StoreError> {
+        let mut stored = self.data.write().unwrap();
+        let project = stored.add_project(name, colour);
+        self.persist(&stored)?;
+        Ok(project)
+    }
+
+    fn update_project(&self, project: &Project) -> Result<(), StoreError> {
+        let mut stored = self.data.write().unwrap();
+        if stored.update_project(project) {
+            self.persist(&stored)?;
+        }
+        Ok(())
+    }
+
+    fn list_invoices(&self) -> Result<Vec<Invoice>, StoreError> {
+        Ok(self.data.read().unwrap().invoices.clone())
+    }
+
+    fn issue_invoice(&self, invoice: Invoice) -> Result<Option<Invoice>, StoreError> {
+        let mut stored = self.data.write().unwrap();
+        let Some(invoice) = stored.issue_invoice(invoice) else {
+            return Ok(None);
+        };
+        self.persist(&stored)?;
+        Ok(Some(invoice))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_file_of_logged_days_only() {
+        let path =
+            std::env::temp_dir().join(format!("hours_counter-days-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"[
+                {"date": [2025, 2, 3], "hours": 8, "minutes": 30},
+                {"date": [2025, 2, 4], "hours": 6, "minutes": 0}
+            ]"#,
+        )
+        .unwrap();
+
+        let store = JsonFileStore::open(&path).unwrap();
+        let days = store.get_range((2025, 2, 1), (2025, 2, 28)).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].0, (2025, 2, 3));
+        assert_eq!((days[0].1.hours(), days[0].1.minutes()), (8, 30));
+        assert_eq!((days[1].1.hours(), days[1].1.minutes()), (6, 0));
+    }
+
+    #[test]
+    fn working_fridays_become_overrides() {
+        let path =
+            std::env::temp_dir().join(format!("hours_counter-fridays-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{"days": [], "working_fridays": [[2025, 2, 7], [2025, 2, 21]]}"#,
+        )
+        .unwrap();
+
+        let store = JsonFileStore::open(&path).unwrap();
+        store.set_friday_override((2025, 2, 21), None).unwrap();
+        let reopened = JsonFileStore::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            reopened
+                .list_friday_overrides((2025, 2, 1), (2025, 2, 28))
+                .unwrap(),
+            vec![((2025, 2, 7), true)]
+        );
+    }
+
+    #[test]
+    fn settings_become_the_default_users() {
+        let path = std::env::temp_dir().join(format!(
+            "hours_counter-settings-{}.json",
+            std::process::id()
+        ));
+        let settings = UserSettings {
+            employee_name: "Jan Kowalski".to_string(),
+            ..UserSettings::default()
+        };
+        fs::write(
+            &path,
+            format!(
+                r#"{{"days": [], "settings": {}}}"#,
+                serde_json::to_string(&settings).unwrap()
+            ),
+        )
+        .unwrap();
+
+        let store = JsonFileStore::open(&path).unwrap();
+        store
+            .save_settings("anna", &UserSettings::default())
+            .unwrap();
+        let reopened = JsonFileStore::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reopened.load_settings("").unwrap(), Some(settings));
+        assert_eq!(
+            reopened.load_settings("anna").unwrap(),
+            Some(UserSettings::default())
+        );
+    }
+}
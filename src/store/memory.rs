@@ -0,0 +1,294 @@
+use super::{StoreError, TimeStore};
+use crate::models::{
+    billing::Invoice,
+    calendar_state::CalendarDate,
+    day_data::DayData,
+    flexitime::BalanceAdjustment,
+    project::Project,
+    timer::{log_session, Timer},
+    user_settings::UserSettings,
+};
+use chrono::{Datelike, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Everything a store keeps, ordered by date
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StoreData {
+    pub days: BTreeMap<CalendarDate, DayData>,
+    pub friday_overrides: BTreeMap<CalendarDate, bool>,
+    /// Settings of each user
+    pub settings: BTreeMap<String, UserSettings>,
+    /// Flexitime balance adjustments, ordered by date and then id
+    pub adjustments: Vec<BalanceAdjustment>,
+    pub timer: Option<Timer>,
+    /// Projects, ordered by id
+    pub projects: Vec<Project>,
+    /// Issued invoices, in the order they were issued
+    pub invoices: Vec<Invoice>,
+}
+
+impl StoreData {
+    pub fn get_range(&self, from: CalendarDate, to: CalendarDate) -> Vec<(CalendarDate, DayData)> {
+        if from > to {
+            return Vec::new();
+        }
+        self.days
+            .range(from..=to)
+            .map(|(date, data)| (*date, data.clone()))
+            .collect()
+    }
+
+    pub fn list_friday_overrides(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Vec<(CalendarDate, bool)> {
+        if from > to {
+            return Vec::new();
+        }
+        self.friday_overrides
+            .range(from..=to)
+            .map(|(date, working)| (*date, *working))
+            .collect()
+    }
+
+    /// Set or clear a Friday override, returning whether anything changed
+    pub fn set_friday_override(&mut self, date: CalendarDate, working: Option<bool>) -> bool {
+        match working {
+            Some(working) => self.friday_overrides.insert(date, working) != Some(working),
+            None => self.friday_overrides.remove(&date).is_some(),
+        }
+    }
+
+    pub fn list_adjustments(&self, from: CalendarDate, to: CalendarDate) -> Vec<BalanceAdjustment> {
+        self.adjustments
+            .iter()
+            .filter(|adjustment| from <= adjustment.date && adjustment.date <= to)
+            .cloned()
+            .collect()
+    }
+
+    /// Add an adjustment under the next free id
+    pub fn add_adjustment(
+        &mut self,
+        date: CalendarDate,
+        minutes: i32,
+        note: &str,
+    ) -> BalanceAdjustment {
+        let adjustment = BalanceAdjustment {
+            id: self
+                .adjustments
+                .iter()
+                .map(|adjustment| adjustment.id)
+                .max()
+                .unwrap_or(0)
+                + 1,
+            date,
+            minutes,
+            note: note.to_string(),
+        };
+        let index = self
+            .adjustments
+            .partition_point(|other| other.date <= adjustment.date);
+        self.adjustments.insert(index, adjustment.clone());
+        adjustment
+    }
+
+    /// Add an active project under the next free id
+    pub fn add_project(&mut self, name: &str, colour: &str) -> Project {
+        let project = Project {
+            id: self.projects.last().map_or(0, |project| project.id) + 1,
+            name: name.to_string(),
+            colour: colour.to_string(),
+            active: true,
+            client: String::new(),
+            rates: Vec::new(),
+        };
+        self.projects.push(project.clone());
+        project
+    }
+
+    /// Replace a project, returning whether it existed
+    pub fn update_project(&mut self, project: &Project) -> bool {
+        match self
+            .projects
+            .iter_mut()
+            .find(|other| other.id == project.id)
+        {
+            Some(stored) => {
+                *stored = project.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number an invoice after the last one issued in the same year and keep it, unless its
+    /// project was already invoiced for the same month
+    pub fn issue_invoice(&mut self, mut invoice: Invoice) -> Option<Invoice> {
+        if self
+            .invoices
+            .iter()
+            .any(|other| other.bills_same_time(&invoice))
+        {
+            return None;
+        }
+        invoice.sequence = self
+            .invoices
+            .iter()
+            .filter(|other| other.issued.year() == invoice.issued.year())
+            .map(|other| other.sequence)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.invoices.push(invoice.clone());
+        Some(invoice)
+    }
+
+    /// Log the stopwatch's runs onto the days they cover, at most 24 hours a day, and remove it,
+    /// returning whether it was in use
+    pub fn stop_timer(&mut self, now: NaiveDateTime) -> bool {
+        let Some(timer) = self.timer.take() else {
+            return false;
+        };
+        for (date, session) in timer.sessions(now) {
+            let day = log_session(self.days.get(&date), session);
+            self.days.insert(date, day);
+        }
+        true
+    }
+
+    /// Remove an adjustment, returning whether it existed
+    pub fn delete_adjustment(&mut self, id: u64) -> bool {
+        let count = self.adjustments.len();
+        self.adjustments.retain(|adjustment| adjustment.id != id);
+        self.adjustments.len() != count
+    }
+}
+
+/// Store that keeps everything in memory and loses it on restart, meant for tests
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    data: RwLock<StoreData>,
+}
+
+impl MemoryStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TimeStore for MemoryStore {
+    fn get_range(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, DayData)>, StoreError> {
+        Ok(self.data.read().unwrap().get_range(from, to))
+    }
+
+    fn upsert_day(&self, date: CalendarDate, data: DayData) -> Result<(), StoreError> {
+        self.data.write().unwrap().days.insert(date, data);
+        Ok(())
+    }
+
+    fn delete_day(&self, date: CalendarDate) -> Result<(), StoreError> {
+        self.data.write().unwrap().days.remove(&date);
+        Ok(())
+    }
+
+    fn list_friday_overrides(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, bool)>, StoreError> {
+        Ok(self.data.read().unwrap().list_friday_overrides(from, to))
+    }
+
+    fn set_friday_override(
+        &self,
+        date: CalendarDate,
+        working: Option<bool>,
+    ) -> Result<(), StoreError> {
+        self.data
+            .write()
+            .unwrap()
+            .set_friday_override(date, working);
+        Ok(())
+    }
+
+    fn load_settings(&self, user: &str) -> Result<Option<UserSettings>, StoreError> {
+        Ok(self.data.read().unwrap().settings.get(user).cloned())
+    }
+
+    fn save_settings(&self, user: &str, settings: &UserSettings) -> Result<(), StoreError> {
+        self.data
+            .write()
+            .unwrap()
+            .settings
+            .insert(user.to_string(), settings.clone());
+        Ok(())
+    }
+
+    fn list_adjustments(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<BalanceAdjustment>, StoreError> {
+        Ok(self.data.read().unwrap().list_adjustments(from, to))
+    }
+
+    fn add_adjustment(
+        &self,
+        date: CalendarDate,
+        minutes: i32,
+        note: &str,
+    ) -> Result<BalanceAdjustment, StoreError> {
+        Ok(self
+            .data
+            .write()
+            .unwrap()
+            .add_adjustment(date, minutes, note))
+    }
+
+    fn delete_adjustment(&self, id: u64) -> Result<(), StoreError> {
+        self.data.write().unwrap().delete_adjustment(id);
+        Ok(())
+    }
+
+    fn load_timer(&self) -> Result<Option<Timer>, StoreError> {
+        Ok(self.data.read().unwrap().timer.clone())
+    }
+
+    fn save_timer(&self, timer: Option<&Timer>) -> Result<(), StoreError> {
+        self.data.write().unwrap().timer = timer.cloned();
+        Ok(())
+    }
+
+    fn stop_timer(&self, now: NaiveDateTime) -> Result<bool, StoreError> {
+        Ok(self.data.write().unwrap().stop_timer(now))
+    }
+
+    fn list_projects(&self) -> Result<Vec<Project>, StoreError> {
+        Ok(self.data.read().unwrap().projects.clone())
+    }
+
+    fn add_project(&self, name: &str, colour: &str) -> Result<Project, StoreError> {
+        Ok(self.data.write().unwrap().add_project(name, colour))
+    }
+
+    fn update_project(&self, project: &Project) -> Result<(), StoreError> {
+        self.data.write().unwrap().update_project(project);
+        Ok(())
+    }
+
+    fn list_invoices(&self) -> Result<Vec<Invoice>, StoreError> {
+        Ok(self.data.read().unwrap().invoices.clone())
+    }
+
+    fn issue_invoice(&self, invoice: Invoice) -> Result<Option<Invoice>, StoreError> {
+        Ok(self.data.write().unwrap().issue_invoice(invoice))
+    }
+}
@@ -0,0 +1,458 @@
+use super::{StoreError, TimeStore};
+use crate::models::{
+    billing::Invoice,
+    calendar_state::CalendarDate,
+    day_data::{DayData, EntryKind},
+    flexitime::BalanceAdjustment,
+    project::Project,
+    timer::{log_session, Timer},
+    user_settings::UserSettings,
+};
+use chrono::NaiveDateTime;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Schema migrations, applied in order; the schema version is kept in `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE days (
+        date TEXT PRIMARY KEY NOT NULL,
+        hours INTEGER NOT NULL,
+        minutes INTEGER NOT NULL
+    );
+    CREATE TABLE working_fridays (
+        date TEXT PRIMARY KEY NOT NULL
+    );",
+    "CREATE TABLE friday_overrides (
+        date TEXT PRIMARY KEY NOT NULL,
+        working INTEGER NOT NULL
+    );
+    INSERT INTO friday_overrides (date, working) SELECT date, 1 FROM working_fridays;
+    DROP TABLE working_fridays;",
+    "CREATE TABLE settings (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        value TEXT NOT NULL
+    );",
+    "CREATE TABLE balance_adjustments (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        date TEXT NOT NULL,
+        minutes INTEGER NOT NULL,
+        note TEXT NOT NULL
+    );
+    CREATE INDEX balance_adjustments_date ON balance_adjustments (date);",
+    "ALTER TABLE days ADD COLUMN kind TEXT NOT NULL DEFAULT 'Work';",
+    "ALTER TABLE days ADD COLUMN span TEXT;",
+    "ALTER TABLE days ADD COLUMN sessions TEXT;
+    UPDATE days
+    SET sessions = json_array(json_object('hours', hours, 'minutes', minutes, 'span', json(span))),
+        span = NULL
+    WHERE span IS NOT NULL;",
+    "CREATE TABLE timer (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        value TEXT NOT NULL
+    );",
+    "CREATE TABLE projects (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        colour TEXT NOT NULL,
+        active INTEGER NOT NULL
+    );",
+    "ALTER TABLE projects ADD COLUMN client TEXT NOT NULL DEFAULT '';
+    ALTER TABLE projects ADD COLUMN rates TEXT NOT NULL DEFAULT '[]';",
+    "CREATE TABLE invoices (
+        year INTEGER NOT NULL,
+        sequence INTEGER NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (year, sequence)
+    );",
+    "CREATE TABLE user_settings (
+        user TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );
+    INSERT INTO user_settings (user, value) SELECT '', value FROM settings;
+    DROP TABLE settings;",
+];
+
+/// Store backed by a SQLite database
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open the database at `path`, creating it and applying pending migrations
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut conn = Connection::open(path)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+/// Bring the schema up to date
+fn migrate(conn: &mut Connection) -> Result<(), StoreError> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(StoreError::Config(format!(
+            "database schema version {version} is newer than this build supports"
+        )));
+    }
+
+    let tx = conn.transaction()?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Format a date as `YYYY-MM-DD` so that text order matches date order
+fn date_key((year, month, day): CalendarDate) -> String {
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Name of an entry kind as stored in the `kind` column
+fn kind_key(kind: EntryKind) -> String {
+    format!("{kind:?}")
+}
+
+fn parse_kind_key(key: &str) -> rusqlite::Result<EntryKind> {
+    EntryKind::ALL
+        .into_iter()
+        .find(|kind| kind_key(*kind) == key)
+        .ok_or_else(|| {
+            rusqlite::Error::InvalidColumnType(3, key.to_string(), rusqlite::types::Type::Text)
+        })
+}
+
+fn parse_date_key(key: &str) -> rusqlite::Result<CalendarDate> {
+    let invalid =
+        || rusqlite::Error::InvalidColumnType(0, key.to_string(), rusqlite::types::Type::Text);
+    let mut parts = key.splitn(3, '-');
+    let mut next = || parts.next().ok_or_else(invalid);
+    let year = next()?.parse().map_err(|_| invalid())?;
+    let month = next()?.parse().map_err(|_| invalid())?;
+    let day = next()?.parse().map_err(|_| invalid())?;
+    Ok((year, month, day))
+}
+
+fn read_days(
+    conn: &Connection,
+    from: CalendarDate,
+    to: CalendarDate,
+) -> Result<Vec<(CalendarDate, DayData)>, StoreError> {
+    let mut stmt = conn.prepare(
+        "SELECT date, hours, minutes, kind, sessions FROM days
+         WHERE date BETWEEN ?1 AND ?2 ORDER BY date",
+    )?;
+    let rows = stmt.query_map(params![date_key(from), date_key(to)], |row| {
+        let date = parse_date_key(&row.get::<_, String>(0)?)?;
+        let kind = parse_kind_key(&row.get::<_, String>(3)?)?;
+        let data = match row.get::<_, Option<String>>(4)? {
+            Some(sessions) => {
+                DayData::from_sessions(serde_json::from_str(&sessions).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        4,
+                        rusqlite::types::Type::Text,
+                        e.into(),
+                    )
+                })?)
+            }
+            None => match (row.get(1)?, row.get(2)?) {
+                (0, 0) => DayData::default(),
+                (hours, minutes) => DayData::new(hours, minutes),
+            },
+        };
+        Ok((date, data.with_kind(kind)))
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn write_day(conn: &Connection, date: CalendarDate, data: &DayData) -> Result<(), StoreError> {
+    conn.execute(
+        "INSERT INTO days (date, hours, minutes, kind, sessions) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (date) DO UPDATE SET hours = excluded.hours,
+            minutes = excluded.minutes, kind = excluded.kind, sessions = excluded.sessions",
+        params![
+            date_key(date),
+            data.hours(),
+            data.minutes(),
+            kind_key(data.kind()),
+            serde_json::to_string(data.sessions())?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn read_timer(conn: &Connection) -> Result<Option<Timer>, StoreError> {
+    let value = conn
+        .query_row("SELECT value FROM timer WHERE id = 1", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .optional()?;
+    Ok(value
+        .map(|value| serde_json::from_str(&value))
+        .transpose()?)
+}
+
+impl TimeStore for SqliteStore {
+    fn get_range(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, DayData)>, StoreError> {
+        read_days(&self.conn.lock().unwrap(), from, to)
+    }
+
+    fn upsert_day(&self, date: CalendarDate, data: DayData) -> Result<(), StoreError> {
+        write_day(&self.conn.lock().unwrap(), date, &data)
+    }
+
+    fn delete_day(&self, date: CalendarDate) -> Result<(), StoreError> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM days WHERE date = ?1", params![date_key(date)])?;
+        Ok(())
+    }
+
+    fn list_friday_overrides(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<(CalendarDate, bool)>, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, working FROM friday_overrides WHERE date BETWEEN ?1 AND ?2 ORDER BY date",
+        )?;
+        let rows = stmt.query_map(params![date_key(from), date_key(to)], |row| {
+            Ok((parse_date_key(&row.get::<_, String>(0)?)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn set_friday_override(
+        &self,
+        date: CalendarDate,
+        working: Option<bool>,
+    ) -> Result<(), StoreError> {
+        let conn = self.conn.lock().unwrap();
+        match working {
+            Some(working) => conn.execute(
+                "INSERT INTO friday_overrides (date, working) VALUES (?1, ?2)
+                 ON CONFLICT (date) DO UPDATE SET working = excluded.working",
+                params![date_key(date), working],
+            )?,
+            None => conn.execute(
+                "DELETE FROM friday_overrides WHERE date = ?1",
+                params![date_key(date)],
+            )?,
+        };
+        Ok(())
+    }
+
+    fn load_settings(&self, user: &str) -> Result<Option<UserSettings>, StoreError> {
+        let value = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT value FROM user_settings WHERE user = ?1",
+                params![user],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    fn save_settings(&self, user: &str, settings: &UserSettings) -> Result<(), StoreError> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO user_settings (user, value) VALUES (?1, ?2)
+             ON CONFLICT (user) DO UPDATE SET value = excluded.value",
+            params![user, serde_json::to_string(settings)?],
+        )?;
+        Ok(())
+    }
+
+    fn list_adjustments(
+        &self,
+        from: CalendarDate,
+        to: CalendarDate,
+    ) -> Result<Vec<BalanceAdjustment>, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, date, minutes, note FROM balance_adjustments
+             WHERE date BETWEEN ?1 AND ?2 ORDER BY date, id",
+        )?;
+        let rows = stmt.query_map(params![date_key(from), date_key(to)], |row| {
+            Ok(BalanceAdjustment {
+                id: row.get(0)?,
+                date: parse_date_key(&row.get::<_, String>(1)?)?,
+                minutes: row.get(2)?,
+                note: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn add_adjustment(
+        &self,
+        date: CalendarDate,
+        minutes: i32,
+        note: &str,
+    ) -> Result<BalanceAdjustment, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO balance_adjustments (date, minutes, note) VALUES (?1, ?2, ?3)",
+            params![date_key(date), minutes, note],
+        )?;
+        Ok(BalanceAdjustment {
+            id: conn.last_insert_rowid() as u64,
+            date,
+            minutes,
+            note: note.to_string(),
+        })
+    }
+
+    fn delete_adjustment(&self, id: u64) -> Result<(), StoreError> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM balance_adjustments WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn load_timer(&self) -> Result<Option<Timer>, StoreError> {
+        read_timer(&self.conn.lock().unwrap())
+    }
+
+    fn save_timer(&self, timer: Option<&Timer>) -> Result<(), StoreError> {
+        let conn = self.conn.lock().unwrap();
+        match timer {
+            Some(timer) => conn.execute(
+                "INSERT INTO timer (id, value) VALUES (1, ?1)
+                 ON CONFLICT (id) DO UPDATE SET value = excluded.value",
+                params![serde_json::to_string(timer)?],
+            )?,
+            None => conn.execute("DELETE FROM timer", [])?,
+        };
+        Ok(())
+    }
+
+    fn stop_timer(&self, now: NaiveDateTime) -> Result<bool, StoreError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let Some(timer) = read_timer(&tx)? else {
+            return Ok(false);
+        };
+        for (date, session) in timer.sessions(now) {
+            let day = read_days(&tx, date, date)?.pop().map(|(_, data)| data);
+            write_day(&tx, date, &log_session(day.as_ref(), session))?;
+        }
+        tx.execute("DELETE FROM timer", [])?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn list_projects(&self) -> Result<Vec<Project>, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, name, colour, active, client, rates FROM projects ORDER BY id")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                Project {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    colour: row.get(2)?,
+                    active: row.get(3)?,
+                    client: row.get(4)?,
+                    rates: Vec::new(),
+                },
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (project, rates) = row?;
+            Ok(Project {
+                rates: serde_json::from_str(&rates)?,
+                ..project
+            })
+        })
+        .collect()
+    }
+
+    fn add_project(&self, name: &str, colour: &str) -> Result<Project, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO projects (name, colour, active) VALUES (?1, ?2, 1)",
+            params![name, colour],
+        )?;
+        Ok(Project {
+            id: conn.last_insert_rowid() as u64,
+            name: name.to_string(),
+            colour: colour.to_string(),
+            active: true,
+            client: String::new(),
+            rates: Vec::new(),
+        })
+    }
+
+    fn update_project(&self, project: &Project) -> Result<(), StoreError> {
+        self.conn.lock().unwrap().execute(
+            "UPDATE projects SET name = ?2, colour = ?3, active = ?4, client = ?5, rates = ?6
+             WHERE id = ?1",
+            params![
+                project.id,
+                project.name,
+                project.colour,
+                project.active,
+                project.client,
+                serde_json::to_string(&project.rates)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn list_invoices(&self) -> Result<Vec<Invoice>, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT value FROM invoices ORDER BY rowid")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|value| Ok(serde_json::from_str(&value?)?))
+            .collect()
+    }
+
+    fn issue_invoice(&self, mut invoice: Invoice) -> Result<Option<Invoice>, StoreError> {
+        use chrono::Datelike;
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for value in tx
+            .prepare("SELECT value FROM invoices")?
+            .query_map([], |row| row.get::<_, String>(0))?
+        {
+            if serde_json::from_str::<Invoice>(&value?)?.bills_same_time(&invoice) {
+                return Ok(None);
+            }
+        }
+        let year = invoice.issued.year();
+        invoice.sequence = tx.query_row(
+            "SELECT COALESCE(MAX(sequence), 0) + 1 FROM invoices WHERE year = ?1",
+            params![year],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO invoices (year, sequence, value) VALUES (?1, ?2, ?3)",
+            params![year, invoice.sequence, serde_json::to_string(&invoice)?],
+        )?;
+        tx.commit()?;
+        Ok(Some(invoice))
+    }
+}